@@ -1,15 +1,17 @@
+use std::{env, path::Path};
+
 use mimalloc::MiMalloc;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
-use std::path::Path;
-
-use langlib::{interpreter::Interpreter, lexer::Lexer};
+use langlib::interpreter::Interpreter;
 
 fn main() -> Result<(), langlib::interpreter::Err> {
-    let s = "class Poop";
-    let tokens = Lexer::new(s).tokenize()?;
-    println!("{tokens:?}");
-    Ok(())
+    // Run the script given on the command line, or the example script in the repo
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("test.lt"));
+
+    Interpreter::from_file(Path::new(&path))?.interpret()
 }
@@ -0,0 +1,812 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, BitAnd, BitOr, BitXor, Mul, Neg, Not, Shl, Shr, Sub},
+    str::FromStr,
+};
+
+/// The biggest power of ten that fits in a digit, which is used to convert to and from decimal.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+
+/// The number of decimal digits in `DECIMAL_BASE`.
+const DECIMAL_DIGITS: usize = 9;
+
+/// An integer of any size, which is what ints become once they don't fit in an `i64` anymore.
+///
+/// The magnitude is stored as base 2^32 digits with the least significant one first. There are never any leading zero
+/// digits, so zero has no digits at all and is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    /// Creates a bigint out of a sign and a magnitude, stripping the leading zeros of the magnitude.
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        trim(&mut digits);
+
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Converts the bigint into an `i64`, if it fits in one.
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0u64, |acc, digit| (acc << 32) | u64::from(*digit));
+
+        if self.negative {
+            // The magnitude of `i64::MIN` is one more than `i64::MAX`, so it has to be subtracted from zero
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Converts the bigint into the closest float.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0.0, |acc, digit| acc * 4_294_967_296.0 + f64::from(*digit));
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Returns the number of bits needed to write down the magnitude.
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(top) => (self.digits.len() as u64 - 1) * 32 + u64::from(32 - top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    /// Divides by `other`, rounding towards zero like ints do, and returns the quotient and the remainder. The
+    /// remainder has the same sign as `self`. Returns `None` when dividing by zero.
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_magnitudes(&self.digits, &other.digits);
+
+        Some((
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        ))
+    }
+
+    /// Raises the bigint to the power of `exp`.
+    pub fn pow(&self, mut exp: u64) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1);
+
+        // Square and multiply, which goes through the bits of the exponent from the lowest one up
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+
+            exp >>= 1;
+
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    /// Returns the bigint in two's complement with `len` digits, which has to be more than the number of digits of the
+    /// magnitude so that the top bit is the sign.
+    fn twos_complement(&self, len: usize) -> Vec<u32> {
+        let mut digits = self.digits.clone();
+        digits.resize(len, 0);
+
+        if self.negative {
+            negate_twos_complement(&mut digits);
+        }
+
+        digits
+    }
+
+    /// Creates a bigint out of its two's complement.
+    fn from_twos_complement(mut digits: Vec<u32>) -> Self {
+        let negative = digits.last().is_some_and(|top| top >> 31 == 1);
+
+        if negative {
+            negate_twos_complement(&mut digits);
+        }
+
+        BigInt::new(negative, digits)
+    }
+
+    /// Applies a bitwise operator to every digit of both bigints, which behave as if they were in two's complement
+    /// like ints are.
+    fn bitwise(&self, other: &BigInt, op: impl Fn(u32, u32) -> u32) -> BigInt {
+        let len = self.digits.len().max(other.digits.len()) + 1;
+
+        let digits = self
+            .twos_complement(len)
+            .into_iter()
+            .zip(other.twos_complement(len))
+            .map(|(lhs, rhs)| op(lhs, rhs))
+            .collect();
+
+        BigInt::from_twos_complement(digits)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(num: i64) -> Self {
+        let magnitude = num.unsigned_abs();
+
+        BigInt::new(num < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[error("Only decimal digits with an optional leading '-' can be parsed into a bigint.")]
+pub struct ParseBigIntError;
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, decimal) = match s.strip_prefix('-') {
+            Some(decimal) => (true, decimal),
+            None => (false, s),
+        };
+
+        if decimal.is_empty() || !decimal.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut digits = Vec::new();
+
+        // Go through the decimal digits from the most significant one, a whole `DECIMAL_BASE` digit at a time
+        for chunk in decimal.as_bytes().chunks(DECIMAL_DIGITS) {
+            let value = chunk
+                .iter()
+                .fold(0, |acc, c| acc * 10 + u32::from(c - b'0'));
+
+            mul_add_small(&mut digits, 10u32.pow(chunk.len() as u32), value);
+        }
+
+        Ok(BigInt::new(negative, digits))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut digits = self.digits.clone();
+        let mut chunks = Vec::new();
+
+        // Split the magnitude into decimal chunks, starting from the least significant one
+        while !digits.is_empty() {
+            chunks.push(div_rem_small(&mut digits, DECIMAL_BASE));
+            trim(&mut digits);
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        let mut chunks = chunks.into_iter().rev();
+
+        if let Some(first) = chunks.next() {
+            write!(f, "{first}")?;
+        }
+
+        // Every chunk after the first one is padded, so that zeros in the middle of the number aren't lost
+        chunks.try_for_each(|chunk| write!(f, "{chunk:0width$}", width = DECIMAL_DIGITS))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitudes(&self.digits, &other.digits),
+
+            // The bigger the magnitude of a negative number, the smaller it is
+            (true, true) => cmp_magnitudes(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitudes(&self.digits, &other.digits));
+        }
+
+        // The signs are different, so the smaller magnitude gets taken away from the bigger one
+        match cmp_magnitudes(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::new(other.negative, sub_magnitudes(&other.digits, &self.digits))
+            }
+            _ => BigInt::new(self.negative, sub_magnitudes(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_magnitudes(&self.digits, &other.digits),
+        )
+    }
+}
+
+impl Not for &BigInt {
+    type Output = BigInt;
+
+    /// Flips every bit of the two's complement, which is the same as `-self - 1`.
+    fn not(self) -> BigInt {
+        &-self - &BigInt::from(1)
+    }
+}
+
+impl BitAnd for &BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |lhs, rhs| lhs & rhs)
+    }
+}
+
+impl BitOr for &BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |lhs, rhs| lhs | rhs)
+    }
+}
+
+impl BitXor for &BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |lhs, rhs| lhs ^ rhs)
+    }
+}
+
+impl Shl<u64> for &BigInt {
+    type Output = BigInt;
+
+    /// Multiplies the bigint by 2^`shift`.
+    fn shl(self, shift: u64) -> BigInt {
+        if self.is_zero() {
+            return BigInt::default();
+        }
+
+        let bits = shift % 32;
+        let mut digits = vec![0u32; (shift / 32) as usize];
+        let mut carry = 0;
+
+        for digit in &self.digits {
+            let shifted = (u64::from(*digit) << bits) | carry;
+
+            digits.push(shifted as u32);
+            carry = shifted >> 32;
+        }
+
+        digits.push(carry as u32);
+
+        BigInt::new(self.negative, digits)
+    }
+}
+
+impl Shr<u64> for &BigInt {
+    type Output = BigInt;
+
+    /// Divides the bigint by 2^`shift`, rounding down like an arithmetic shift of an int does.
+    fn shr(self, shift: u64) -> BigInt {
+        // `!self` isn't negative, and shifting it and flipping the bits back rounds down
+        if self.negative {
+            return !&(&!self >> shift);
+        }
+
+        let whole = usize::try_from(shift / 32).unwrap_or(usize::MAX);
+
+        if whole >= self.digits.len() {
+            return BigInt::default();
+        }
+
+        let bits = shift % 32;
+        let mut digits = self.digits[whole..].to_vec();
+
+        // Every digit takes the bits that get shifted out of the one above it
+        for i in 0..digits.len() {
+            let above = u64::from(digits.get(i + 1).copied().unwrap_or(0));
+
+            digits[i] = (((above << 32) | u64::from(digits[i])) >> bits) as u32;
+        }
+
+        BigInt::new(false, digits)
+    }
+}
+
+/// Negates a number in two's complement in place, by flipping its bits and adding one.
+fn negate_twos_complement(digits: &mut [u32]) {
+    let mut carry = true;
+
+    for digit in digits.iter_mut() {
+        (*digit, carry) = (!*digit).overflowing_add(u32::from(carry));
+    }
+}
+
+/// Removes the leading zero digits of a magnitude.
+fn trim(digits: &mut Vec<u32>) {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+}
+
+/// Compares two magnitudes without leading zeros.
+fn cmp_magnitudes(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.len()
+        .cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+fn add_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let (long, short) = if lhs.len() >= rhs.len() {
+        (lhs, rhs)
+    } else {
+        (rhs, lhs)
+    };
+
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+
+    for (i, digit) in long.iter().enumerate() {
+        let sum = u64::from(*digit) + u64::from(short.get(i).copied().unwrap_or(0)) + carry;
+
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    result
+}
+
+/// Subtracts `rhs` from `lhs`, where `lhs` can't be smaller than `rhs`.
+fn sub_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(lhs.len());
+    let mut borrow = 0;
+
+    for (i, digit) in lhs.iter().enumerate() {
+        let diff = i64::from(*digit) - i64::from(rhs.get(i).copied().unwrap_or(0)) - borrow;
+
+        if diff < 0 {
+            result.push((diff + (1 << 32)) as u32);
+            borrow = 1;
+        } else {
+            result.push(diff as u32);
+            borrow = 0;
+        }
+    }
+
+    trim(&mut result);
+    result
+}
+
+fn mul_magnitudes(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    if lhs.is_empty() || rhs.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = vec![0u32; lhs.len() + rhs.len()];
+
+    for (i, a) in lhs.iter().enumerate() {
+        let mut carry = 0;
+
+        for (j, b) in rhs.iter().enumerate() {
+            // This can't overflow, since (2^32 - 1)^2 + 2 * (2^32 - 1) is exactly 2^64 - 1
+            let product = u64::from(*a) * u64::from(*b) + u64::from(result[i + j]) + carry;
+
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+
+        result[i + rhs.len()] = carry as u32;
+    }
+
+    trim(&mut result);
+    result
+}
+
+/// Multiplies a magnitude by `mul` and adds `add` to it, in place.
+fn mul_add_small(digits: &mut Vec<u32>, mul: u32, add: u32) {
+    let mut carry = u64::from(add);
+
+    for digit in digits.iter_mut() {
+        let product = u64::from(*digit) * u64::from(mul) + carry;
+
+        *digit = product as u32;
+        carry = product >> 32;
+    }
+
+    if carry > 0 {
+        digits.push(carry as u32);
+    }
+}
+
+/// Divides a magnitude by `div` in place, and returns the remainder.
+fn div_rem_small(digits: &mut [u32], div: u32) -> u32 {
+    let mut remainder = 0u64;
+
+    for digit in digits.iter_mut().rev() {
+        let current = (remainder << 32) | u64::from(*digit);
+
+        *digit = (current / u64::from(div)) as u32;
+        remainder = current % u64::from(div);
+    }
+
+    remainder as u32
+}
+
+/// Divides two magnitudes, where `rhs` isn't zero, and returns the quotient and the remainder.
+fn div_rem_magnitudes(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitudes(lhs, rhs) == Ordering::Less {
+        return (Vec::new(), lhs.to_vec());
+    }
+
+    if let [div] = rhs {
+        let mut quotient = lhs.to_vec();
+        let remainder = div_rem_small(&mut quotient, *div);
+
+        return (quotient, vec![remainder]);
+    }
+
+    // Long division a whole digit at a time (Knuth's algorithm D). Both sides are shifted so that the top digit of the
+    // divisor has its highest bit set, which makes the guess for every digit of the quotient at most two too big.
+    let shift = rhs.last().map_or(0, |top| top.leading_zeros());
+    let normalize = |digits: &[u32]| {
+        let mut carry = 0;
+        let mut shifted: Vec<u32> = digits
+            .iter()
+            .map(|digit| {
+                let wide = (u64::from(*digit) << shift) | carry;
+                carry = wide >> 32;
+                wide as u32
+            })
+            .collect();
+
+        shifted.push(carry as u32);
+        shifted
+    };
+
+    let mut divisor = normalize(rhs);
+    divisor.pop();
+
+    let mut remainder = normalize(lhs);
+
+    let n = divisor.len();
+    let (top, second) = (u64::from(divisor[n - 1]), u64::from(divisor[n - 2]));
+    let mut quotient = vec![0u32; remainder.len() - n];
+
+    for j in (0..quotient.len()).rev() {
+        // Guess the digit from the top two digits of what's left, and correct the guess with the third one
+        let current = (u64::from(remainder[j + n]) << 32) | u64::from(remainder[j + n - 1]);
+        let mut guess = current / top;
+        let mut rest = current % top;
+
+        while guess > u64::from(u32::MAX)
+            || guess * second > ((rest << 32) | u64::from(remainder[j + n - 2]))
+        {
+            guess -= 1;
+            rest += top;
+
+            if rest > u64::from(u32::MAX) {
+                break;
+            }
+        }
+
+        // Subtract `guess` times the divisor
+        let mut borrow = 0;
+        let mut carry = 0;
+
+        for i in 0..n {
+            let product = guess * u64::from(divisor[i]) + carry;
+            carry = product >> 32;
+
+            let diff = i64::from(remainder[i + j]) - borrow - i64::from(product as u32);
+            remainder[i + j] = diff as u32;
+            borrow = i64::from(diff < 0);
+        }
+
+        let diff = i64::from(remainder[j + n]) - borrow - carry as i64;
+        remainder[j + n] = diff as u32;
+
+        // The guess was still one too big, so the divisor gets added back
+        if diff < 0 {
+            guess -= 1;
+
+            let mut carry = 0;
+
+            for i in 0..n {
+                let sum = u64::from(remainder[i + j]) + u64::from(divisor[i]) + carry;
+                remainder[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+
+            remainder[j + n] = remainder[j + n].wrapping_add(carry as u32);
+        }
+
+        quotient[j] = guess as u32;
+    }
+
+    // Undo the shift of the remainder, which is less than the divisor so it fits in `n` digits
+    let remainder = (0..n)
+        .map(|i| {
+            let wide = (u64::from(remainder[i + 1]) << 32) | u64::from(remainder[i]);
+            (wide >> shift) as u32
+        })
+        .collect();
+
+    trim(&mut quotient);
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod bigint_tests {
+    use std::cmp::Ordering;
+
+    use super::BigInt;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in [
+            "0",
+            "7",
+            "-42",
+            "4294967296",
+            "9223372036854775808",
+            "-170141183460469231731687303715884105728",
+            "100000000000000000000000000001",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+
+        assert_eq!(big("-0"), BigInt::default());
+        assert_eq!(big("000123").to_string(), "123");
+
+        for s in ["", "-", "12a", "+1", "1.5"] {
+            assert!(s.parse::<BigInt>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_i64_conversions() {
+        for num in [0, 1, -1, i64::MAX, i64::MIN, 1 << 32, -(1 << 40)] {
+            assert_eq!(BigInt::from(num).to_i64(), Some(num));
+            assert_eq!(BigInt::from(num).to_string(), num.to_string());
+        }
+
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+        assert_eq!(big("-9223372036854775809").to_i64(), None);
+        assert_eq!(big("-9223372036854775808").to_i64(), Some(i64::MIN));
+        assert_eq!(big("18446744073709551616").to_f64(), 18446744073709551616.0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+
+        assert_eq!((&a + &b).to_string(), "-864197532086419753208641975320");
+        assert_eq!((&a - &b).to_string(), "1111111110111111111011111111100");
+        assert_eq!(
+            (&a * &b).to_string(),
+            "-121932631137021795226185032733622923332237463801111263526900"
+        );
+        assert_eq!((&a - &a), BigInt::default());
+        assert_eq!(
+            big("2").pow(100).to_string(),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(big("-3").pow(3), big("-27"));
+        assert_eq!(big("5").pow(0), big("1"));
+    }
+
+    #[test]
+    fn test_division() {
+        let (quotient, remainder) = big("-987654321098765432109876543210")
+            .div_rem(&big("123456789012345678901234567890"))
+            .unwrap();
+
+        assert_eq!(quotient, big("-8"));
+        assert_eq!(remainder, big("-9000000000900000000090"));
+
+        let (quotient, remainder) = big("100000000000000000000").div_rem(&big("-7")).unwrap();
+
+        assert_eq!(quotient, big("-14285714285714285714"));
+        assert_eq!(remainder, big("2"));
+
+        assert!(big("1").div_rem(&BigInt::default()).is_none());
+    }
+
+    #[test]
+    fn test_long_division() {
+        // A xorshift generator, so that the digits are spread out but the test always checks the same numbers
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut digits = |len: usize| -> Vec<u32> {
+            (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+
+                    // Mix in digits that are all ones or only have the top bit set, which hit the corrections
+                    match state % 8 {
+                        0 => u32::MAX,
+                        1 => 1 << 31,
+                        _ => state as u32,
+                    }
+                })
+                .collect()
+        };
+
+        for (lhs_len, rhs_len) in [(2, 2), (3, 2), (5, 3), (8, 4), (20, 7), (40, 39), (64, 2)] {
+            for _ in 0..50 {
+                let lhs = BigInt::new(false, digits(lhs_len));
+                let rhs = BigInt::new(false, digits(rhs_len));
+
+                let (quotient, remainder) = lhs.div_rem(&rhs).unwrap();
+
+                assert_eq!(&(&quotient * &rhs) + &remainder, lhs, "{lhs} / {rhs}");
+                assert!(remainder < rhs, "{lhs} % {rhs}");
+            }
+        }
+
+        // A guess that's still one too big after the correction, so the divisor has to be added back
+        let lhs = BigInt::new(false, vec![0, 0, 1 << 31, 0x7fff_ffff]);
+        let rhs = BigInt::new(false, vec![1, 0, 1 << 31]);
+        let (quotient, remainder) = lhs.div_rem(&rhs).unwrap();
+
+        assert_eq!(quotient, BigInt::new(false, vec![0xffff_fffe]));
+        assert_eq!(&(&quotient * &rhs) + &remainder, lhs);
+        assert!(remainder < rhs);
+    }
+
+    #[test]
+    fn test_bitwise() {
+        // Bigints have to give the same results as ints wherever both can be used
+        let nums = [
+            0,
+            1,
+            -1,
+            12,
+            -12,
+            1 << 40,
+            -(1 << 40) - 3,
+            i64::MAX,
+            i64::MIN,
+        ];
+
+        for lhs in nums {
+            for rhs in nums {
+                let (a, b) = (BigInt::from(lhs), BigInt::from(rhs));
+
+                assert_eq!(&a & &b, BigInt::from(lhs & rhs), "{lhs} & {rhs}");
+                assert_eq!(&a | &b, BigInt::from(lhs | rhs), "{lhs} | {rhs}");
+                assert_eq!(&a ^ &b, BigInt::from(lhs ^ rhs), "{lhs} ^ {rhs}");
+            }
+
+            assert_eq!(!&BigInt::from(lhs), BigInt::from(!lhs), "~{lhs}");
+
+            for shift in [0, 1, 31, 32, 33, 63] {
+                assert_eq!(
+                    &BigInt::from(lhs) >> shift,
+                    BigInt::from(lhs >> shift),
+                    "{lhs} >> {shift}"
+                );
+            }
+        }
+
+        let a = big("-340282366920938463463374607431768211457");
+
+        assert_eq!(&big("1") << 100, big("1267650600228229401496703205376"));
+        assert_eq!(&big("-3") << 64, big("-55340232221128654848"));
+        assert_eq!(&(&a << 77) >> 77, a);
+        assert_eq!(&a >> 1000, big("-1"));
+        assert_eq!(&big("5") >> 1000, BigInt::default());
+        assert_eq!(
+            &a & &big("18446744073709551615"),
+            big("18446744073709551615")
+        );
+        assert_eq!(&a | &big("1"), a);
+        assert_eq!(&a ^ &a, BigInt::default());
+        assert_eq!(!&a, big("340282366920938463463374607431768211456"));
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut nums = [
+            big("5"),
+            big("-99999999999999999999"),
+            big("0"),
+            big("99999999999999999999"),
+            big("-5"),
+        ];
+        nums.sort();
+
+        let sorted: Vec<String> = nums.iter().map(BigInt::to_string).collect();
+
+        assert_eq!(
+            sorted,
+            [
+                "-99999999999999999999",
+                "-5",
+                "0",
+                "5",
+                "99999999999999999999"
+            ]
+        );
+        assert_eq!(big("-1").cmp(&big("-2")), Ordering::Greater);
+    }
+}
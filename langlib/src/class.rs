@@ -0,0 +1,72 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ahash::AHashMap;
+
+use crate::{func::Closure, interpreter::err::RuntimeErr, value::Value};
+
+/// A class, which creates instances of itself when it's called.
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub name: String,
+    pub superclass: Option<Rc<Class>>,
+    pub methods: AHashMap<String, Closure>,
+}
+
+impl Class {
+    pub fn new(
+        name: String,
+        superclass: Option<Rc<Class>>,
+        methods: AHashMap<String, Closure>,
+    ) -> Self {
+        Self {
+            name,
+            superclass,
+            methods,
+        }
+    }
+
+    /// Looks up a method of the class by its name, going up through the superclasses if the class doesn't have it.
+    pub fn find_method(&self, name: &str) -> Option<&Closure> {
+        match self.methods.get(name) {
+            Some(method) => Some(method),
+            None => self.superclass.as_ref()?.find_method(name),
+        }
+    }
+}
+
+/// An instance of a class, which may be shared and mutated by everything that refers to it.
+pub type InstanceRef = Rc<RefCell<Instance>>;
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub class: Rc<Class>,
+    pub fields: AHashMap<String, Value>,
+}
+
+impl Instance {
+    pub fn new(class: Rc<Class>) -> Self {
+        Self {
+            class,
+            fields: AHashMap::new(),
+        }
+    }
+
+    /// Gets a property of an instance. Fields are looked up first, and methods come with `this` bound to the instance.
+    pub fn get(instance: &InstanceRef, name: &str) -> Result<Value, RuntimeErr> {
+        if let Some(val) = instance.borrow().fields.get(name) {
+            return Ok(val.to_owned());
+        }
+
+        let class = Rc::clone(&instance.borrow().class);
+
+        match class.find_method(name) {
+            Some(method) => Ok(Value::Func(Rc::new(method.bind(Rc::clone(instance))))),
+            None => Err(RuntimeErr::UndefinedProperty(name.to_owned())),
+        }
+    }
+
+    /// Sets a field of the instance.
+    pub fn set(&mut self, name: String, val: Value) {
+        self.fields.insert(name, val);
+    }
+}
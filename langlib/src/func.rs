@@ -1,65 +1,102 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
 use crate::{
-    expr::Expr,
-    interpreter::{self, env::Env, Interpreter},
+    class::InstanceRef,
+    interpreter::{
+        self,
+        env::{Env, EnvRef},
+        Interpreter,
+    },
     stmt::Stmt,
+    value::Value,
 };
-#[derive(Debug, Clone, Eq, PartialEq)]
+
+/// The definition of a function. The body is shared, so that creating closures out of it doesn't copy it.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Func {
-    pub instructions: Box<Stmt>,
+    pub instructions: Rc<Stmt>,
     pub args: Vec<String>,
-    pub closure: Option<Env>,
 }
 
 impl Func {
     pub fn new(instructions: Stmt, args: Vec<String>) -> Self {
         Self {
-            instructions: Box::new(instructions),
+            instructions: Rc::new(instructions),
             args,
-            closure: None,
         }
     }
 
     pub fn arg_len(&self) -> usize {
         self.args.len()
     }
+}
+
+/// A function together with the environment it was created in, which is what a function evaluates to.
+#[derive(Clone)]
+pub struct Closure {
+    pub func: Func,
+    pub env: EnvRef,
+}
+
+impl Closure {
+    pub fn new(func: Func, env: EnvRef) -> Self {
+        Self { func, env }
+    }
+
+    pub fn arg_len(&self) -> usize {
+        self.func.arg_len()
+    }
 
     pub fn exec(
-        self,
+        &self,
         interpreter: &mut Interpreter,
-        args: Vec<Expr>,
-    ) -> Result<Expr, interpreter::Err> {
-        if self.closure.is_none() {
-            panic!("Function has no closure. If you see this message than the code of the interpreter is fucked up.");
-        }
-        
-        let save = interpreter.env.clone().into_inner();
-        
+        args: Vec<Value>,
+    ) -> Result<Value, interpreter::Err> {
         let mut new_env = Env::default();
 
-        new_env.set_parent(self.closure.unwrap());
-        
+        new_env.set_parent(Rc::clone(&self.env));
+
         // Bring all the variables into scope
-        (0..args.len()).for_each(|i| {
-            new_env.define(self.args[i].clone(), args[i].clone());
-        });
-
-        interpreter.env.replace(new_env);
-        
-        let return_val = match interpreter.execute_stmt(&self.instructions) {
-            Ok(_) => Expr::Null,
+        for (name, arg) in self.func.args.iter().zip(args) {
+            new_env.define(name.clone(), arg);
+        }
+
+        let return_val = match interpreter.with_env(new_env, |interpreter| {
+            interpreter.execute_stmt(&self.func.instructions)
+        }) {
+            Ok(_) => Value::Null,
             Err(err) => match err {
-                interpreter::Err::ReturnStmt(expr) => expr,
+                interpreter::Err::ReturnStmt(val) => val,
                 err => return Err(err),
             },
         };
 
-        interpreter.env.replace(save);
-
         Ok(return_val)
     }
 
-    /// Sets the closure of this function.
-    pub fn set_closure(&mut self, closure: Env) {
-        self.closure = Some(closure);
+    /// Creates a method out of this closure, where `this` refers to the given instance.
+    pub fn bind(&self, instance: InstanceRef) -> Self {
+        let mut env = Env::default();
+
+        env.set_parent(Rc::clone(&self.env));
+        env.define(String::from("this"), Value::Instance(instance));
+
+        Self::new(self.func.clone(), Rc::new(RefCell::new(env)))
+    }
+}
+
+impl PartialEq for Closure {
+    /// Environments are compared by identity, since an environment can contain the closure itself.
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.env, &other.env) && self.func == other.func
+    }
+}
+
+impl Debug for Closure {
+    /// Leaves out the environment, which can contain the closure itself.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Closure")
+            .field("func", &self.func)
+            .finish_non_exhaustive()
     }
 }
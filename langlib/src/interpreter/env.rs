@@ -1,20 +1,23 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, rc::Rc};
 
 use ahash::AHashMap;
 
-use crate::expr::Expr;
+use crate::value::Value;
 
 use super::err::RuntimeErr;
 
-#[derive(Debug, Eq, PartialEq, Clone, Default)]
+/// A scope that may be shared, for example between a block and the functions that were created inside of it.
+pub type EnvRef = Rc<RefCell<Env>>;
+
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Env {
-    vals: AHashMap<String, Expr>,
-    pub parent: Option<Box<RefCell<Env>>>,
+    vals: AHashMap<String, Value>,
+    pub parent: Option<EnvRef>,
 }
 
 impl Env {
     /// Tries to get a variable from the environment.
-    pub fn get(&self, k: &str) -> Result<Expr, RuntimeErr> {
+    pub fn get(&self, k: &str) -> Result<Value, RuntimeErr> {
         match self.vals.get(k) {
             Some(v) => Ok(v.to_owned()),
 
@@ -27,37 +30,63 @@ impl Env {
     }
 
     /// Defines a new variable and stores it in the environment.
-    pub fn define(&mut self, k: String, v: Expr) {
+    pub fn define(&mut self, k: String, v: Value) {
         self.vals.insert(k, v);
     }
 
     /// Assigns a value to a variable.
-    pub fn assign(&mut self, k: &str, v: Expr) -> Result<(), RuntimeErr> {
-        match self.vals.get(k) {
-            Some(_) => {
-                self.vals.insert(k.to_owned(), v);
+    pub fn assign(&mut self, k: &str, v: Value) -> Result<(), RuntimeErr> {
+        match self.vals.get_mut(k) {
+            Some(val) => {
+                *val = v;
                 Ok(())
             }
             None => match &self.parent {
-                Some(parent) => RefCell::borrow_mut(parent).assign(k, v),
-                None => todo!(),
+                Some(parent) => parent.borrow_mut().assign(k, v),
+                None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
+            },
+        }
+    }
+
+    /// Gets a variable from the environment which is `depth` parents up from this one.
+    pub fn get_at(&self, depth: usize, k: &str) -> Result<Value, RuntimeErr> {
+        match depth {
+            0 => match self.vals.get(k) {
+                Some(v) => Ok(v.to_owned()),
+                None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
+            },
+            _ => match &self.parent {
+                Some(parent) => parent.borrow().get_at(depth - 1, k),
+                None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
+            },
+        }
+    }
+
+    /// Assigns a value to a variable in the environment which is `depth` parents up from this one.
+    pub fn assign_at(&mut self, depth: usize, k: &str, v: Value) -> Result<(), RuntimeErr> {
+        match depth {
+            0 => match self.vals.get_mut(k) {
+                Some(val) => {
+                    *val = v;
+                    Ok(())
+                }
+                None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
+            },
+            _ => match &self.parent {
+                Some(parent) => parent.borrow_mut().assign_at(depth - 1, k, v),
+                None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
             },
         }
     }
 
     /// Sets the parent of the environment.
-    pub fn set_parent(&mut self, parent: Env) {
-        self.parent = Some(Box::new(RefCell::new(parent)));
+    pub fn set_parent(&mut self, parent: EnvRef) {
+        self.parent = Some(parent);
     }
 
     /// Gets the parent of the environment.
-    pub fn get_parent(self) -> Option<Env> {
-        let p = match self.parent {
-            Some(p) => p,
-            None => return None,
-        };
-
-        Some((*p).into_inner())
+    pub fn get_parent(&self) -> Option<EnvRef> {
+        self.parent.clone()
     }
 
     /// Deletes a variable from the current environment.
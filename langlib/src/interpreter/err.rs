@@ -1,6 +1,9 @@
-use crate::expr::Expr;
+use crate::{
+    bigint::BigInt,
+    expr::{Expr, ExprError},
+};
 
-#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum RuntimeErr {
     #[error("Variable \"{0}\" already exists.")]
     VarRedefine(String),
@@ -12,13 +15,30 @@ pub enum RuntimeErr {
     UnexpectedType(LexerThingType),
     #[error("An unexpected number of arguments was supplied. Expected {0} arguments, found {1}.")]
     BadArgLength(usize, usize),
+    #[error("Index {0} is out of range for a list of length {1}.")]
+    IndexOutOfRange(BigInt, usize),
+    #[error("Only lists and maps can be indexed or iterated over.")]
+    NotACollection,
+    #[error("Property \"{0}\" does not exist.")]
+    UndefinedProperty(String),
+    #[error("Class \"{0}\" can only inherit from a class.")]
+    InvalidSuperclass(String),
+    #[error("Class \"{0}\" can't inherit from itself.")]
+    InheritsFromItself(String),
+    #[error("{0}")]
+    Operator(ExprError),
 }
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum LexerThingType {
     Int,
+    Float,
     Str,
     Bool,
     Null,
     Ident,
     Func,
+    List,
+    Map,
+    Class,
+    Instance,
 }
@@ -1,27 +1,39 @@
 pub mod env;
 pub mod err;
+mod tests;
 
+use ahash::AHashMap;
 use err::RuntimeErr;
 use std::{
     cell::RefCell,
     fs::OpenOptions,
     io::{self, Read},
+    mem,
     path::Path,
+    rc::Rc,
 };
 
 use crate::{
-    expr::{BinExpr, Expr},
-    lexer::{err::LexerError, Lexer},
+    bigint::BigInt,
+    class::{Class, Instance},
+    expr::Expr,
+    func::Closure,
+    lexer::{err::LexerError, op::BinOp, Lexer},
     parser::{err::ParserError, Parser},
+    resolver::{err::ResolverError, Resolver},
     stmt::Stmt,
+    value::{List, Value},
 };
 
-use self::env::Env;
+use self::env::{Env, EnvRef};
 
 #[derive(Debug)]
 pub struct Interpreter {
     instructions: Vec<Stmt>,
-    pub env: RefCell<Env>,
+    /// The environment of the code that's currently running.
+    pub env: EnvRef,
+    /// The top-level environment, where variables that the resolver didn't find in a local scope live.
+    globals: EnvRef,
 }
 
 impl Interpreter {
@@ -32,45 +44,37 @@ impl Interpreter {
 
         file.read_to_string(&mut source)?;
 
-        let stmts = Parser::new(Lexer::new(&source).tokenize()?).get_statements()?;
+        let stmts = Parser::from_lexer(Lexer::new(&source)).get_statements()?;
 
-        Ok(Self {
-            instructions: stmts,
-            env: RefCell::new(Env::default()),
-        })
+        Self::new(stmts)
     }
 
-    pub fn new(instructions: Vec<Stmt>) -> Self {
-        Self {
-            instructions,
-            env: RefCell::new(Env::default()),
-        }
+    /// Creates an interpreter for the given statements, after running the resolver over them. The interpreter relies on
+    /// the scope depths the resolver adds, so statements never reach it without being resolved.
+    pub fn new(instructions: Vec<Stmt>) -> Result<Self, Err> {
+        let globals = EnvRef::default();
+
+        Ok(Self {
+            instructions: Resolver::new().resolve(instructions)?,
+            env: Rc::clone(&globals),
+            globals,
+        })
     }
 
-    /// Interprets the code
-    pub fn interpret(mut self) -> Result<(), Err> {
-        for i in 0..self.instructions.len() {
-            let stmt = self.instructions[i].clone();
-            self.execute_stmt(&stmt)?;
+    /// Interprets the instructions.
+    pub fn interpret(&mut self) -> Result<(), Err> {
+        for stmt in &mem::take(&mut self.instructions) {
+            self.execute_stmt(stmt)?;
         }
 
         Ok(())
     }
 
-    /// Interprets the instructions
-    pub fn execute_stmt(&mut self, stmt: &Stmt) -> Result<(), Err> {
+    /// Interprets a single statement, which must have been resolved already.
+    pub(crate) fn execute_stmt(&mut self, stmt: &Stmt) -> Result<(), Err> {
         match stmt {
             Stmt::Declaration(declaration) => {
-                let mut expr = self.visit_expr(&declaration.val)?;
-
-                // Set the closure of the function if it doesn't have one already
-                if let Expr::Func(func) = &mut expr {
-
-                    if func.closure.is_none() {
-                        func.set_closure(self.env.clone().into_inner());
-                    }
-
-                }
+                let expr = self.visit_expr(&declaration.val)?;
 
                 self.env
                     .borrow_mut()
@@ -88,41 +92,20 @@ impl Interpreter {
             }
 
             Stmt::Block(stmts) => {
-                let prev = self.env.to_owned().into_inner();
-                let save = self.env.to_owned().into_inner();
-
+                // The block runs in a child of the current environment rather than a copy of it, so that assignments
+                // to outer variables outlive the block. Errors, including `return`, are passed on to the caller.
                 let mut new_env = Env::default();
-                new_env.set_parent(prev);
-
-                self.env.replace(new_env);
-
-                let return_val = match stmts.iter().find_map(|stmt| match self.execute_stmt(stmt) {
-                    Ok(_) => None,
-                    Err(err) => match err {
-                        Err::ReturnStmt(expr) => Some(Ok(expr)),
-                        err => return Some(Err(err)),
-                    },
-                }) {
-                    Some(expr) => expr?,
-                    None => Expr::Null,
-                };
-
-                // Restore the original
-                self.env.replace(save);
+                new_env.set_parent(Rc::clone(&self.env));
 
-                // Return the return statement if it's not null
-                if return_val != Expr::Null {
-                    return Err(Err::ReturnStmt(return_val));
-                }
+                self.with_env(new_env, |interpreter| {
+                    stmts
+                        .iter()
+                        .try_for_each(|stmt| interpreter.execute_stmt(stmt))
+                })?;
             }
 
             Stmt::If(expr, block, else_block) => {
-                let result = match self.visit_expr(expr)? {
-                    Expr::Bool(b) => b,
-                    expr => return Err(Err::RuntimeErr(RuntimeErr::InvalidExpr(expr))),
-                };
-
-                if result {
+                if self.visit_expr(expr)?.try_into()? {
                     self.execute_stmt(block)?;
                 } else if let Some(else_block) = else_block {
                     self.execute_stmt(else_block)?;
@@ -130,79 +113,443 @@ impl Interpreter {
             }
             Stmt::While(condition, block) => {
                 while self.visit_expr(condition)?.try_into()? {
-                    self.execute_stmt(block)?;
+                    if !self.execute_loop_body(block)? {
+                        break;
+                    }
+                }
+            }
+            Stmt::For(condition, block, increment) => {
+                while self.visit_expr(condition)?.try_into()? {
+                    if !self.execute_loop_body(block)? {
+                        break;
+                    }
+
+                    self.execute_stmt(increment)?;
                 }
             }
             Stmt::Assignment(declaration) => {
                 let expr = self.visit_expr(&declaration.val)?;
 
-                self.env.borrow_mut().assign(&declaration.ident, expr)?;
+                self.globals.borrow_mut().assign(&declaration.ident, expr)?;
+            }
+            Stmt::LocalAssignment(declaration, depth) => {
+                let expr = self.visit_expr(&declaration.val)?;
+
+                self.env
+                    .borrow_mut()
+                    .assign_at(*depth, &declaration.ident, expr)?;
+            }
+            Stmt::IndexAssignment(assignment) => {
+                let target = self.visit_expr(&assignment.target)?;
+                let index = self.visit_expr(&assignment.index)?;
+                let val = self.visit_expr(&assignment.val)?;
+
+                if let Value::Map(map) = target {
+                    let key = Self::map_key(index)?;
+
+                    let val = match &assignment.op {
+                        Some(op) => {
+                            let old = map.borrow().get(&key).cloned().unwrap_or(Value::Null);
+
+                            Self::apply_op(op, old, val)?
+                        }
+                        None => val,
+                    };
+
+                    map.borrow_mut().insert(key, val);
+
+                    return Ok(());
+                }
+
+                let (list, i) = Self::list_index(target, index)?;
+
+                let val = match &assignment.op {
+                    Some(op) => Self::apply_op(op, list.borrow()[i].clone(), val)?,
+                    None => val,
+                };
+
+                list.borrow_mut()[i] = val;
+            }
+            Stmt::PropertyAssignment(assignment) => {
+                let instance = match self.visit_expr(&assignment.target)? {
+                    Value::Instance(instance) => instance,
+                    _ => {
+                        return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                            err::LexerThingType::Instance,
+                        )))
+                    }
+                };
+
+                let val = self.visit_expr(&assignment.val)?;
+
+                let val = match &assignment.op {
+                    Some(op) => {
+                        Self::apply_op(op, Instance::get(&instance, &assignment.name)?, val)?
+                    }
+                    None => val,
+                };
+
+                instance.borrow_mut().set(assignment.name.clone(), val);
+            }
+            Stmt::Class(decl) => {
+                let superclass = match &decl.superclass {
+                    Some(Expr::Var(name) | Expr::Local(name, _)) if name == &decl.name => {
+                        return Err(Err::RuntimeErr(RuntimeErr::InheritsFromItself(
+                            decl.name.clone(),
+                        )))
+                    }
+                    Some(superclass) => match self.visit_expr(superclass)? {
+                        Value::Class(superclass) => Some(superclass),
+                        _ => {
+                            return Err(Err::RuntimeErr(RuntimeErr::InvalidSuperclass(
+                                decl.name.clone(),
+                            )))
+                        }
+                    },
+                    None => None,
+                };
+
+                let mut closure = Rc::clone(&self.env);
+
+                // Methods of a subclass can reach the superclass through `super`
+                if let Some(superclass) = &superclass {
+                    let mut env = Env::default();
+
+                    env.set_parent(closure);
+                    env.define(String::from("super"), Value::Class(Rc::clone(superclass)));
+
+                    closure = Rc::new(RefCell::new(env));
+                }
+
+                let methods = decl
+                    .methods
+                    .iter()
+                    .map(|(name, method)| {
+                        let method = Closure::new(method.clone(), Rc::clone(&closure));
+
+                        (name.clone(), method)
+                    })
+                    .collect();
+
+                let class = Class::new(decl.name.clone(), superclass, methods);
+
+                self.env
+                    .borrow_mut()
+                    .define(decl.name.clone(), Value::Class(Rc::new(class)));
+            }
+            Stmt::ForIn(for_in) => {
+                // Take the entries up front, so that the loop body can change the collection while it's looped over
+                let (entries, is_map): (Vec<(Value, Value)>, bool) =
+                    match self.visit_expr(&for_in.iterable)? {
+                        Value::List(list) => (
+                            list.borrow()
+                                .iter()
+                                .enumerate()
+                                .map(|(i, item)| (Value::Num(i as i64), item.clone()))
+                                .collect(),
+                            false,
+                        ),
+                        Value::Map(map) => {
+                            let mut entries: Vec<(String, Value)> = map
+                                .borrow()
+                                .iter()
+                                .map(|(key, val)| (key.clone(), val.clone()))
+                                .collect();
+
+                            // Sort the keys so that maps are always looped over in the same order
+                            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                            (
+                                entries
+                                    .into_iter()
+                                    .map(|(key, val)| (Value::Str(key), val))
+                                    .collect(),
+                                true,
+                            )
+                        }
+                        _ => return Err(Err::RuntimeErr(RuntimeErr::NotACollection)),
+                    };
+
+                for (first, second) in entries {
+                    let mut new_env = Env::default();
+                    new_env.set_parent(Rc::clone(&self.env));
+
+                    match &for_in.second {
+                        Some(ident) => {
+                            new_env.define(for_in.first.clone(), first);
+                            new_env.define(ident.clone(), second);
+                        }
+
+                        // A single variable gets the items of a list and the keys of a map
+                        None => match is_map {
+                            true => new_env.define(for_in.first.clone(), first),
+                            false => new_env.define(for_in.first.clone(), second),
+                        },
+                    }
+
+                    let keep_going = self.with_env(new_env, |interpreter| {
+                        interpreter.execute_loop_body(&for_in.body)
+                    })?;
+
+                    if !keep_going {
+                        break;
+                    }
+                }
             }
             Stmt::Return(expr) => {
                 return Err(Err::ReturnStmt(self.visit_expr(expr)?));
             }
+            Stmt::Break => return Err(Err::Break),
+            Stmt::Continue => return Err(Err::Continue),
         }
 
         Ok(())
     }
 
-    /// Visits an expression and executes it.
-    fn visit_expr(&mut self, expr: &Expr) -> Result<Expr, Err> {
+    /// Checks that `target` is a list and that `index` is an int within its range, returning both.
+    fn list_index(target: Value, index: Value) -> Result<(List, usize), Err> {
+        let list = match target {
+            Value::List(list) => list,
+            _ => return Err(Err::RuntimeErr(RuntimeErr::NotACollection)),
+        };
+
+        let len = list.borrow().len();
+
+        // A bignum is never in range, but it's still an int so it's reported as being out of range
+        match index {
+            Value::Num(index) => match usize::try_from(index) {
+                Ok(i) if i < len => Ok((list, i)),
+                _ => Err(Err::RuntimeErr(RuntimeErr::IndexOutOfRange(
+                    BigInt::from(index),
+                    len,
+                ))),
+            },
+            Value::BigNum(index) => Err(Err::RuntimeErr(RuntimeErr::IndexOutOfRange(index, len))),
+            _ => Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                err::LexerThingType::Int,
+            ))),
+        }
+    }
+
+    /// Calls a function with the given arguments.
+    fn call(&mut self, func: &Closure, args: Vec<Value>) -> Result<Value, Err> {
+        if func.arg_len() != args.len() {
+            return Err(Err::RuntimeErr(RuntimeErr::BadArgLength(
+                func.arg_len(),
+                args.len(),
+            )));
+        }
+
+        func.exec(self, args)
+    }
+
+    /// Checks that `key` can be used as the key of a map, which only strings can.
+    fn map_key(key: Value) -> Result<String, Err> {
+        match key {
+            Value::Str(key) => Ok(key),
+            _ => Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                err::LexerThingType::Str,
+            ))),
+        }
+    }
+
+    /// Applies a binary operator, such as the operator of a compound assignment like `a[i] += v` to the old and new
+    /// value.
+    fn apply_op(op: &BinOp, lhs: Value, rhs: Value) -> Result<Value, Err> {
+        Value::binary(op, lhs, rhs).map_err(Self::operator_err)
+    }
+
+    /// Turns an error from applying an operator into a runtime error, since things like overflow are only found out
+    /// while the code runs.
+    fn operator_err(err: ParserError) -> Err {
+        match err {
+            ParserError::ExprError(err) => Err::RuntimeErr(RuntimeErr::Operator(err)),
+            err => Err::ParserError(err),
+        }
+    }
+
+    /// Runs `f` with `env` as the current environment, and restores the current environment afterwards.
+    pub fn with_env<T>(&mut self, env: Env, f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.env, Rc::new(RefCell::new(env)));
+
+        let result = f(self);
+
+        self.env = previous;
+
+        result
+    }
+
+    /// Executes one iteration of a loop, returning a boolean indicating whether the loop should keep going.
+    fn execute_loop_body(&mut self, body: &Stmt) -> Result<bool, Err> {
+        match self.execute_stmt(body) {
+            Ok(_) | Err(Err::Continue) => Ok(true),
+            Err(Err::Break) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Visits an expression and evaluates it into a value.
+    fn visit_expr(&mut self, expr: &Expr) -> Result<Value, Err> {
         match expr {
-            Expr::Var(var) => match self.env.borrow().get(var) {
+            Expr::Num(num) => Ok(Value::Num(*num)),
+            Expr::BigNum(num) => Ok(Value::BigNum(num.clone())),
+            Expr::Float(num) => Ok(Value::Float(*num)),
+            Expr::Str(string) => Ok(Value::Str(string.clone())),
+            Expr::Bool(bool) => Ok(Value::Bool(*bool)),
+            Expr::Null => Ok(Value::Null),
+            Expr::Var(var) => match self.globals.borrow().get(var) {
                 Ok(val) => Ok(val),
                 Err(err) => Err(Err::RuntimeErr(err)),
             },
+            Expr::Local(var, depth) => Ok(self.env.borrow().get_at(*depth, var)?),
             Expr::Bin(bin_expr) => {
                 let lhs = self.visit_expr(&bin_expr.lhs)?;
 
+                // Logical operators only evaluate the right side if the left one doesn't already decide the result
+                if let BinOp::And | BinOp::Or = bin_expr.op {
+                    return match lhs.decides(&bin_expr.op)? {
+                        true => Ok(lhs),
+                        false => self.visit_expr(&bin_expr.rhs),
+                    };
+                }
+
                 let rhs = self.visit_expr(&bin_expr.rhs)?;
 
-                match Expr::eval(&Expr::Bin(BinExpr {
-                    lhs: Box::new(lhs),
-                    rhs: Box::new(rhs),
-                    op: bin_expr.op.clone(),
-                })) {
-                    Ok(result) => Ok(result),
-                    Err(err) => Err(Err::ParserError(err)),
+                Self::apply_op(&bin_expr.op, lhs, rhs)
+            }
+            // Functions capture the environment they're created in
+            Expr::Func(func) => Ok(Value::Func(Rc::new(Closure::new(
+                func.clone(),
+                Rc::clone(&self.env),
+            )))),
+            Expr::List(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.visit_expr(item))
+                    .try_collect()?;
+
+                Ok(Value::List(Rc::new(RefCell::new(items))))
+            }
+            Expr::Map(entries) => {
+                let mut map = AHashMap::with_capacity(entries.len());
+
+                for (key, val) in entries {
+                    let key = Self::map_key(self.visit_expr(key)?)?;
+
+                    map.insert(key, self.visit_expr(val)?);
                 }
+
+                Ok(Value::Map(Rc::new(RefCell::new(map))))
             }
-            Expr::Unary(op, expr) => match Expr::eval(&Expr::Unary(op.clone(), expr.clone())) {
-                Ok(val) => Ok(val),
-                Err(err) => Err(Err::ParserError(err)),
-            },
+            Expr::Index(target, index) => {
+                let target = self.visit_expr(target)?;
+                let index = self.visit_expr(index)?;
 
-            Expr::Funcall(callee, args) => {
-                let func = self.visit_expr(callee)?;
+                // Looking up a key that isn't in a map gives null
+                if let Value::Map(map) = target {
+                    let key = Self::map_key(index)?;
 
-                let func = match func {
-                    Expr::Func(func) => Ok(func),
-                    _ => Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
-                        err::LexerThingType::Func,
-                    ))),
-                }?;
+                    let val = map.borrow().get(&key).cloned().unwrap_or(Value::Null);
+
+                    return Ok(val);
+                }
+
+                let (list, i) = Self::list_index(target, index)?;
+
+                let item = list.borrow()[i].clone();
+
+                Ok(item)
+            }
+            Expr::Ternary(condition, then, otherwise) => {
+                match self.visit_expr(condition)?.try_into()? {
+                    true => self.visit_expr(then),
+                    false => self.visit_expr(otherwise),
+                }
+            }
+            Expr::Unary(op, expr) => {
+                let val = self.visit_expr(expr)?;
+
+                val.unary(op).map_err(Self::operator_err)
+            }
 
-                let args: Vec<Expr> = args
+            Expr::Funcall(callee, args) => {
+                let callee = self.visit_expr(callee)?;
+
+                let args: Vec<Value> = args
                     .iter()
                     .map(|expr| self.visit_expr(expr))
                     .try_collect()?;
 
-                if func.arg_len() != args.len() {
-                    return Err(Err::RuntimeErr(RuntimeErr::BadArgLength(
-                        func.arg_len(),
-                        args.len(),
-                    )));
+                match callee {
+                    Value::Func(func) => self.call(&func, args),
+
+                    // Calling a class creates an instance of it, which `init` gets to set up
+                    Value::Class(class) => {
+                        let instance = Rc::new(RefCell::new(Instance::new(Rc::clone(&class))));
+
+                        match class.find_method("init") {
+                            Some(init) => {
+                                self.call(&init.bind(Rc::clone(&instance)), args)?;
+                            }
+                            None if !args.is_empty() => {
+                                return Err(Err::RuntimeErr(RuntimeErr::BadArgLength(
+                                    0,
+                                    args.len(),
+                                )))
+                            }
+                            None => {}
+                        }
+
+                        Ok(Value::Instance(instance))
+                    }
+                    _ => Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                        err::LexerThingType::Func,
+                    ))),
                 }
-
-                func.exec(self, args)
             }
+            Expr::Get(target, name) => match self.visit_expr(target)? {
+                Value::Instance(instance) => Ok(Instance::get(&instance, name)?),
+                _ => Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                    err::LexerThingType::Instance,
+                ))),
+            },
+            // `this` and `super` are always turned into locals by the resolver
+            Expr::This => Err(Err::RuntimeErr(RuntimeErr::UndefinedVar(String::from(
+                "this",
+            )))),
+            Expr::Super(_) => Err(Err::RuntimeErr(RuntimeErr::UndefinedVar(String::from(
+                "super",
+            )))),
+            Expr::LocalSuper(name, depth) => {
+                let superclass = match self.env.borrow().get_at(*depth, "super")? {
+                    Value::Class(superclass) => superclass,
+                    _ => {
+                        return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                            err::LexerThingType::Class,
+                        )))
+                    }
+                };
+
+                let instance = match self.env.borrow().get_at(depth - 1, "this")? {
+                    Value::Instance(instance) => instance,
+                    _ => {
+                        return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                            err::LexerThingType::Instance,
+                        )))
+                    }
+                };
 
-            _ => Ok(expr.clone()),
+                // The method is looked up from the superclass, but still works on the same instance
+                match superclass.find_method(name) {
+                    Some(method) => Ok(Value::Func(Rc::new(method.bind(instance)))),
+                    None => Err(Err::RuntimeErr(RuntimeErr::UndefinedProperty(name.clone()))),
+                }
+            }
         }
     }
 
     /// Helper functions for other structs, defines a variable in the internal env.
-    pub fn define_var(&self, k: String, v: Expr) {
+    pub fn define_var(&self, k: String, v: Value) {
         self.env.borrow_mut().define(k, v);
     }
 }
@@ -213,12 +560,20 @@ pub enum Err {
     ParserError(#[from] ParserError),
     #[error("An error occurred during lexing.")]
     LexerError(#[from] LexerError),
-    #[error("A runtime error has occured.")]
+    #[error("An error occurred during resolving: {0}")]
+    ResolverError(#[from] ResolverError),
+    #[error("A runtime error has occured: {0}")]
     RuntimeErr(#[from] RuntimeErr),
 
     #[error("An IO error occured while attempting to read the file.")]
     IOError(#[from] io::Error),
 
     #[error("Not really an error.")]
-    ReturnStmt(Expr),
+    ReturnStmt(Value),
+
+    #[error("Not really an error either.")]
+    Break,
+
+    #[error("Not really an error either.")]
+    Continue,
 }
@@ -0,0 +1,764 @@
+#[cfg(test)]
+/// A module for interpreter unit tests.
+mod interpreter_tests {
+    use crate::{
+        bigint::BigInt,
+        expr::ExprError,
+        interpreter::{
+            err::{LexerThingType, RuntimeErr},
+            Err, Interpreter,
+        },
+        lexer::{op::BinOp, Lexer},
+        parser::Parser,
+        resolver::err::ResolverError,
+        value::Value,
+    };
+
+    /// Runs the source code and returns the interpreter, so that the variables it defined can be inspected.
+    fn run(source: &str) -> Result<Interpreter, Err> {
+        let stmts = Parser::from_lexer(Lexer::new(source)).get_statements()?;
+
+        let mut interpreter = Interpreter::new(stmts)?;
+        interpreter.interpret()?;
+
+        Ok(interpreter)
+    }
+
+    /// Returns the value of a variable.
+    fn get(interpreter: &Interpreter, var: &str) -> Value {
+        interpreter.env.borrow().get(var).unwrap()
+    }
+
+    /// Returns the items of a list variable. Lists are compared by identity, so their items are compared instead.
+    fn items(interpreter: &Interpreter, var: &str) -> Vec<Value> {
+        match get(interpreter, var) {
+            Value::List(list) => list.borrow().clone(),
+            other => panic!("Expected a list, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolves_on_creation() {
+        let stmts = Parser::from_lexer(Lexer::new("{ let a = 1; let a = 2; }"))
+            .get_statements()
+            .unwrap();
+
+        assert!(matches!(
+            Interpreter::new(stmts),
+            Err(Err::ResolverError(ResolverError::Redeclared(_)))
+        ));
+
+        // Locals are only found through the depths the resolver gives them
+        let interpreter = run("let x = 0; { let a = 5; x = a; }").unwrap();
+        assert_eq!(get(&interpreter, "x"), Value::Num(5));
+    }
+
+    #[test]
+    fn test_eval_non_literals() {
+        let interpreter = run("
+            let a = 1;
+            func f() {
+                return 2;
+            }
+            let sum = a + 1;
+            let items = [1, 2];
+            let called = f();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "sum"), Value::Num(2));
+        assert_eq!(items(&interpreter, "items"), [Value::Num(1), Value::Num(2)]);
+        assert_eq!(get(&interpreter, "called"), Value::Num(2));
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        let interpreter =
+            run("let x = -5; let y = -x; let z = - -x * 2; let w = -2.5; let b = !!true;").unwrap();
+
+        assert_eq!(get(&interpreter, "x"), Value::Num(-5));
+        assert_eq!(get(&interpreter, "y"), Value::Num(5));
+        assert_eq!(get(&interpreter, "z"), Value::Num(-10));
+        assert_eq!(get(&interpreter, "w"), Value::Float(-2.5));
+        assert_eq!(get(&interpreter, "b"), Value::Bool(true));
+
+        assert!(run("let x = -\"a string\";").is_err());
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        let interpreter =
+            run("let i = 10; i += 5; i -= 1; i *= 3; i /= 2; i %= 8; let f = 1.5; f *= 2;")
+                .unwrap();
+
+        assert_eq!(get(&interpreter, "i"), Value::Num(5));
+        assert_eq!(get(&interpreter, "f"), Value::Float(3.0));
+    }
+
+    #[test]
+    fn test_bignums() {
+        let interpreter = run("
+            let a = 9223372036854775807 + 1;
+            let b = a - 1;
+            let c = 100000000000000000000;
+            let square = 99999999999999999999 * 99999999999999999999;
+            let pow = 2 ** 100;
+            let negated = -(-1 << 63);
+            let divided = (-1 << 63) / -1;
+            let rem = (-1 << 63) % -1;
+            let shifted = 3 << 64;
+            let halved = -(a + 1) ~/ 2;
+            let floor_mod = c % 7;
+            let neg_floor_mod = -c % 7;
+            let floor_div = c ~/ -7;
+            let tripled = a;
+            tripled *= 3;
+            let compared = a > 9223372036854775807 and a == 9223372036854775808 and -a < 0 and a > 1.5;
+            let as_float = a * 1.0;
+            let huge_quotient = (1 << 262000) / ((1 << 261000) + 1) == (1 << 1000) - 1;
+        ")
+        .unwrap();
+
+        let big = |s: &str| Value::BigNum(s.parse().unwrap());
+
+        assert_eq!(get(&interpreter, "a"), big("9223372036854775808"));
+        assert_eq!(get(&interpreter, "b"), Value::Num(i64::MAX));
+        assert_eq!(get(&interpreter, "c"), big("100000000000000000000"));
+        assert_eq!(
+            get(&interpreter, "square"),
+            big("9999999999999999999800000000000000000001")
+        );
+        assert_eq!(
+            get(&interpreter, "pow"),
+            big("1267650600228229401496703205376")
+        );
+        assert_eq!(get(&interpreter, "negated"), big("9223372036854775808"));
+        assert_eq!(get(&interpreter, "divided"), big("9223372036854775808"));
+        assert_eq!(get(&interpreter, "rem"), Value::Num(0));
+        assert_eq!(get(&interpreter, "shifted"), big("55340232221128654848"));
+        assert_eq!(
+            get(&interpreter, "halved"),
+            Value::Num(-4611686018427387905)
+        );
+        assert_eq!(get(&interpreter, "floor_mod"), Value::Num(2));
+        assert_eq!(get(&interpreter, "neg_floor_mod"), Value::Num(5));
+        assert_eq!(get(&interpreter, "floor_div"), big("-14285714285714285715"));
+        assert_eq!(get(&interpreter, "tripled"), big("27670116110564327424"));
+        assert_eq!(get(&interpreter, "compared"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "huge_quotient"), Value::Bool(true));
+        assert_eq!(
+            get(&interpreter, "as_float"),
+            Value::Float(9223372036854775808.0)
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_errors() {
+        let cases = [
+            (
+                "let a = 1 / 0;",
+                ExprError::DivisionByZero(BinOp::Div, 1.into()),
+            ),
+            (
+                "let a = 5 % 0;",
+                ExprError::DivisionByZero(BinOp::Mod, 5.into()),
+            ),
+            (
+                "let a = -5 ~/ 0;",
+                ExprError::DivisionByZero(BinOp::FloorDiv, (-5).into()),
+            ),
+            (
+                "let a = 100000000000000000000 / 0;",
+                ExprError::DivisionByZero(BinOp::Div, "100000000000000000000".parse().unwrap()),
+            ),
+            (
+                "let a = 3 ** 1000000;",
+                ExprError::TooBig(BinOp::Pow, 3.into(), 1000000.into()),
+            ),
+            (
+                "let a = 5 << 1000000;",
+                ExprError::TooBig(BinOp::Shl, 5.into(), 1000000.into()),
+            ),
+            ("let a = 1 >> -2;", ExprError::ShiftOutOfRange((-2).into())),
+        ];
+
+        for (s, expected) in cases {
+            match run(s) {
+                Err(Err::RuntimeErr(RuntimeErr::Operator(err))) => assert_eq!(err, expected, "{s}"),
+                other => panic!("expected an operator error for {s}, got {other:?}"),
+            }
+        }
+
+        // Results that grow too big one step at a time are caught too
+        for s in [
+            "let x = (1 << 262000) * (1 << 200);",
+            "let x = 1 << 262000; while (true) x = x + x;",
+            "let x = -(1 << 262143); x -= 1 << 262143;",
+        ] {
+            assert!(
+                matches!(
+                    run(s),
+                    Err(Err::RuntimeErr(RuntimeErr::Operator(ExprError::TooBig(..))))
+                ),
+                "{s}"
+            );
+        }
+
+        // The messages include the operands
+        let err = run("let a = 7; a /= 0;").unwrap_err();
+        assert!(err.to_string().contains("7 / 0"), "{err}");
+
+        let err = run("let a = 3 ** 1000000;").unwrap_err();
+        assert!(err.to_string().contains("3 ** 1000000 is too big"), "{err}");
+
+        let err = run("let a = -(2 ** 64) << 1000000;").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("-18446744073709551616 << 1000000 is too big"),
+            "{err}"
+        );
+
+        // Floats follow IEEE 754 instead
+        let interpreter = run("let inf = 1.0 / 0;").unwrap();
+
+        assert_eq!(get(&interpreter, "inf"), Value::Float(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_short_circuit() {
+        let interpreter = run("
+            let a = null or \"default\";
+            let b = 0 and 5;
+            let c = \"x\" and 2;
+            let d = false and undefined;
+            let e = true or undefined;
+            let f = null;
+            if (f != null and f > 1) {
+                f = 1;
+            }
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Str("default".to_owned()));
+        assert_eq!(get(&interpreter, "b"), Value::Num(0));
+        assert_eq!(get(&interpreter, "c"), Value::Num(2));
+        assert_eq!(get(&interpreter, "d"), Value::Bool(false));
+        assert_eq!(get(&interpreter, "e"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "f"), Value::Null);
+
+        assert!(run("let x = true and undefined;").is_err());
+    }
+
+    #[test]
+    fn test_else_if_chain() {
+        let interpreter = run("
+            let i = 0;
+            let small = 0;
+            let medium = 0;
+            let large = 0;
+            while (i < 10) {
+                if (i < 3) small += 1;
+                else if (i < 6) medium += 1;
+                else large += 1;
+                i += 1;
+            }
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "small"), Value::Num(3));
+        assert_eq!(get(&interpreter, "medium"), Value::Num(3));
+        assert_eq!(get(&interpreter, "large"), Value::Num(4));
+    }
+
+    #[test]
+    fn test_truthy_conditions() {
+        let interpreter = run("
+            let n = 3;
+            let loops = 0;
+            while (n) {
+                n -= 1;
+                loops += 1;
+            }
+
+            let s = \"abc\";
+            for (let i = 0; s; i += 1) {
+                if (i == 2) s = \"\";
+            }
+
+            let taken = 0;
+            if (\"non-empty\") taken += 1;
+            if (null) taken += 10;
+            if (0) taken += 100;
+            if (2.5) taken += 1000;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "n"), Value::Num(0));
+        assert_eq!(get(&interpreter, "loops"), Value::Num(3));
+        assert_eq!(get(&interpreter, "s"), Value::Str(String::new()));
+        assert_eq!(get(&interpreter, "taken"), Value::Num(1001));
+
+        assert!(run("if ([1]) print 1;").is_err());
+        assert!(run("while ({\"a\": 1}) print 1;").is_err());
+    }
+
+    #[test]
+    fn test_block_scoping() {
+        let interpreter = run("
+            let outer = 1;
+            {
+                let inner = 2;
+                outer = outer + inner;
+                {
+                    outer *= 10;
+                }
+            }
+
+            let find = func(limit) {
+                let i = 0;
+                while (true) {
+                    {
+                        if (i * i > limit) {
+                            return i;
+                        }
+                    }
+                    i += 1;
+                }
+            };
+            let found = find(20);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "outer"), Value::Num(30));
+        assert_eq!(get(&interpreter, "found"), Value::Num(5));
+        assert!(interpreter.env.borrow().get("inner").is_err());
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let interpreter = run("
+            let odd_sum = 0;
+            for (let i = 0; i < 10; i += 1) {
+                if (i % 2 == 0) continue;
+                odd_sum += i;
+            }
+
+            let j = 0;
+            while (true) {
+                j += 1;
+                if (j == 5) break;
+            }
+
+            let pairs = 0;
+            for (let a = 0; a < 3; a += 1) {
+                for (let b = 0; b < 3; b += 1) {
+                    if (b > a) break;
+                    pairs += 1;
+                }
+            }
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "odd_sum"), Value::Num(25));
+        assert_eq!(get(&interpreter, "j"), Value::Num(5));
+        assert_eq!(get(&interpreter, "pairs"), Value::Num(6));
+    }
+
+    #[test]
+    fn test_ternary() {
+        let interpreter = run("
+            let x = 5;
+            let size = x < 3 ? \"small\" : x < 10 ? \"medium\" : \"large\";
+            let lazy = true ? 1 : undefined;
+            let nested = false ? undefined : (x > 0 ? x : -x);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "size"), Value::Str("medium".to_owned()));
+        assert_eq!(get(&interpreter, "lazy"), Value::Num(1));
+        assert_eq!(get(&interpreter, "nested"), Value::Num(5));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        let interpreter = run("
+            func apply(f, x) {
+                return f(x);
+            }
+
+            let doubled = apply(func (n) { return n * 2; }, 21);
+
+            let k = 3;
+            let mul = func (n) { return n * k; };
+            let tripled = mul(4);
+
+            func adder(a) {
+                return func (b) { return a + b; };
+            }
+            let seven = adder(2)(5);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "doubled"), Value::Num(42));
+        assert_eq!(get(&interpreter, "tripled"), Value::Num(12));
+        assert_eq!(get(&interpreter, "seven"), Value::Num(7));
+    }
+
+    #[test]
+    fn test_lists() {
+        let interpreter = run("
+            let a = [1, 2, 3];
+            let b = a;
+            b[0] = 10;
+            a[2] *= 5;
+
+            let sum = 0;
+            for (let i = 0; i < 3; i += 1) {
+                sum += a[i];
+            }
+
+            let grid = [[0, 1], [2, 3]];
+            let corner = grid[1][1];
+
+            let calls = [0];
+            func index() {
+                calls[0] += 1;
+                return 0;
+            }
+            let counts = [5];
+            counts[index()] += 1;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "sum"), Value::Num(27));
+        assert_eq!(
+            items(&interpreter, "a"),
+            [Value::Num(10), Value::Num(2), Value::Num(15)]
+        );
+        assert_eq!(get(&interpreter, "b"), get(&interpreter, "a"));
+        assert_eq!(get(&interpreter, "corner"), Value::Num(3));
+        assert_eq!(items(&interpreter, "calls"), [Value::Num(1)]);
+        assert_eq!(items(&interpreter, "counts"), [Value::Num(6)]);
+    }
+
+    #[test]
+    fn test_self_containing_collections() {
+        let interpreter = run("
+            let l = [\"a\", \"b\"];
+            l[1] = l;
+            let alias = l;
+            let same = l == l and l == alias and l[1] == l;
+            let copy_equal = [\"a\"] == [\"a\"];
+            let list_text = \"\" + l;
+
+            let m = {\"k\": \"v\"};
+            m[\"self\"] = m;
+            let map_text = \"\" + m;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "same"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "copy_equal"), Value::Bool(false));
+        assert_eq!(
+            get(&interpreter, "list_text"),
+            Value::Str("[a, [...]]".to_owned())
+        );
+        assert_eq!(
+            get(&interpreter, "map_text"),
+            Value::Str("{\"k\": v, \"self\": {...}}".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_list_errors() {
+        assert!(matches!(
+            run("let a = [1]; let b = a[1];"),
+            Err(Err::RuntimeErr(RuntimeErr::IndexOutOfRange(ref index, 1))) if *index == BigInt::from(1)
+        ));
+        assert!(matches!(
+            run("let a = [1]; a[-1] = 2;"),
+            Err(Err::RuntimeErr(RuntimeErr::IndexOutOfRange(ref index, 1))) if *index == BigInt::from(-1)
+        ));
+        assert!(matches!(
+            run("let a = [1]; let b = a[100000000000000000000];"),
+            Err(Err::RuntimeErr(RuntimeErr::IndexOutOfRange(ref index, 1)))
+                if index.to_string() == "100000000000000000000"
+        ));
+        assert!(matches!(
+            run("let a = [1]; let b = a[\"0\"];"),
+            Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                LexerThingType::Int
+            )))
+        ));
+        assert!(matches!(
+            run("let a = 1; let b = a[0];"),
+            Err(Err::RuntimeErr(RuntimeErr::NotACollection))
+        ));
+    }
+
+    #[test]
+    fn test_maps() {
+        let interpreter = run("
+            let record = {\"name\": \"pump\", \"count\": 2,};
+            let alias = record;
+            alias[\"count\"] += 3;
+            record[\"id\"] = 7;
+
+            let name = record[\"name\"];
+            let missing = record[\"nope\"];
+
+            let keys = \"\";
+            let total = 0;
+            for (key, value in {\"b\": 2, \"a\": 1, \"c\": 3}) {
+                keys = keys + key;
+                total += value;
+            }
+
+            let only_keys = \"\";
+            for (key in {\"y\": 0, \"x\": 0}) only_keys = only_keys + key;
+
+            let items = 0;
+            for (i, item in [10, 20]) items += i * item;
+
+            {\"unused\": 1};
+            {\"a\" + \"b\": 2}[\"ab\"];
+            {};
+            {}[\"x\"];
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "name"), Value::Str("pump".to_owned()));
+        assert_eq!(get(&interpreter, "missing"), Value::Null);
+        assert_eq!(get(&interpreter, "keys"), Value::Str("abc".to_owned()));
+        assert_eq!(get(&interpreter, "total"), Value::Num(6));
+        assert_eq!(get(&interpreter, "only_keys"), Value::Str("xy".to_owned()));
+        assert_eq!(get(&interpreter, "items"), Value::Num(20));
+
+        match get(&interpreter, "alias") {
+            Value::Map(map) => {
+                assert_eq!(map.borrow()["count"], Value::Num(5));
+                assert_eq!(map.borrow()["id"], Value::Num(7));
+            }
+            other => panic!("Expected a map, found {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_map_errors() {
+        assert!(matches!(
+            run("let m = {1: 2};"),
+            Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                LexerThingType::Str
+            )))
+        ));
+        assert!(matches!(
+            run("for (x in 5) print x;"),
+            Err(Err::RuntimeErr(RuntimeErr::NotACollection))
+        ));
+    }
+
+    #[test]
+    fn test_classes() {
+        let interpreter = run("
+            class Counter {
+                init(start) {
+                    this.count = start;
+                }
+
+                add(n) {
+                    this.count += n;
+                    return this;
+                }
+
+                get() {
+                    return this.count;
+                }
+            }
+
+            let counter = Counter(5);
+            counter.add(2).add(3);
+            let count = counter.get();
+
+            let get = counter.get;
+            counter.count = 100;
+            let bound = get();
+
+            class Empty {
+                describe() {
+                    return \"empty\";
+                }
+            }
+
+            let empty = Empty();
+            empty.label = \"box\";
+            let label = empty.label;
+            let description = empty.describe();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "count"), Value::Num(10));
+        assert_eq!(get(&interpreter, "bound"), Value::Num(100));
+        assert_eq!(get(&interpreter, "label"), Value::Str("box".to_owned()));
+        assert_eq!(
+            get(&interpreter, "description"),
+            Value::Str("empty".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_instance_equality() {
+        let interpreter = run("
+            class A {}
+            class B {}
+            let a = A();
+            a.me = a;
+            let separate = A() == A();
+            let same = a == a and a.me == a;
+            let classes = A == A and A != B;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "separate"), Value::Bool(false));
+        assert_eq!(get(&interpreter, "same"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "classes"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_class_errors() {
+        assert!(matches!(
+            run("class A { f() { return 1; } } let a = A(); let b = a.missing;"),
+            Err(Err::RuntimeErr(RuntimeErr::UndefinedProperty(_)))
+        ));
+        assert!(matches!(
+            run("class A { f() { return 1; } } let a = A(1);"),
+            Err(Err::RuntimeErr(RuntimeErr::BadArgLength(0, 1)))
+        ));
+        assert!(matches!(
+            run("let a = 1; a.b = 2;"),
+            Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                LexerThingType::Instance
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let interpreter = run("
+            class Animal {
+                init(name) {
+                    this.name = name;
+                }
+
+                speak() {
+                    return this.name + \" makes a sound\";
+                }
+
+                kind() {
+                    return \"animal\";
+                }
+            }
+
+            class Dog < Animal {
+                init(name) {
+                    super.init(name);
+                    this.tricks = 0;
+                }
+
+                speak() {
+                    return super.speak() + \" and barks\";
+                }
+            }
+
+            class Puppy < Dog {
+                speak() {
+                    return super.speak() + \" quietly\";
+                }
+            }
+
+            let puppy = Puppy(\"Rex\");
+            let speech = puppy.speak();
+            let kind = puppy.kind();
+            let tricks = puppy.tricks;
+        ")
+        .unwrap();
+
+        assert_eq!(
+            get(&interpreter, "speech"),
+            Value::Str("Rex makes a sound and barks quietly".to_owned())
+        );
+        assert_eq!(get(&interpreter, "kind"), Value::Str("animal".to_owned()));
+        assert_eq!(get(&interpreter, "tricks"), Value::Num(0));
+
+        // A class can be redefined to inherit from its old definition
+        let interpreter = run(
+            "class A { f() { return 1; } } let B = A; class A < B { g() { return 2; } } let x = A().f() + A().g();",
+        )
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "x"), Value::Num(3));
+    }
+
+    #[test]
+    fn test_inheritance_errors() {
+        assert!(matches!(
+            run("let A = 1; class B < A { f() { return 1; } }"),
+            Err(Err::RuntimeErr(RuntimeErr::InvalidSuperclass(_)))
+        ));
+        assert!(matches!(
+            run("class A < A { f() { return 1; } }"),
+            Err(Err::RuntimeErr(RuntimeErr::InheritsFromItself(_)))
+        ));
+        assert!(matches!(
+            run("class A { f() { return 1; } } class B < A { g() { return super.missing(); } } let x = B().g();"),
+            Err(Err::RuntimeErr(RuntimeErr::UndefinedProperty(_)))
+        ));
+    }
+
+    #[test]
+    fn test_shared_closures() {
+        let interpreter = run("
+            func makeCounter() {
+                let i = 0;
+                func count() {
+                    i = i + 1;
+                    return i;
+                }
+
+                return count;
+            }
+
+            let counter = makeCounter();
+            counter();
+            counter();
+            let third = counter();
+
+            let other = makeCounter();
+            let first = other();
+
+            let shared = 0;
+            let inc = func () { shared += 1; };
+            let read = func () { return shared; };
+            inc();
+            inc();
+            let seen = read();
+
+            func fib(n) {
+                if (n < 2) return n;
+                return fib(n - 1) + fib(n - 2);
+            }
+            let fib10 = fib(10);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "third"), Value::Num(3));
+        assert_eq!(get(&interpreter, "first"), Value::Num(1));
+        assert_eq!(get(&interpreter, "shared"), Value::Num(2));
+        assert_eq!(get(&interpreter, "seen"), Value::Num(2));
+        assert_eq!(get(&interpreter, "fib10"), Value::Num(55));
+
+        assert!(matches!(
+            run("undefined = 1;"),
+            Err(Err::RuntimeErr(RuntimeErr::UndefinedVar(_)))
+        ));
+    }
+}
@@ -1,13 +1,53 @@
-use std::num::IntErrorKind;
+use std::num::{IntErrorKind, ParseFloatError};
+
+use super::span::Span;
 
 #[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 pub enum LexerError {
-    #[error("Failed to parse int")]
-    IntError(IntErrorKind),
-    #[error("Invalid token encountered")]
-    InvalidChar(char),
+    #[error("Failed to parse int at {1}")]
+    IntError(IntErrorKind, Span),
+    #[error("Failed to parse float at {1}")]
+    FloatError(ParseFloatError, Span),
+    #[error("Invalid token '{0}' encountered at {1}")]
+    InvalidChar(char, Span),
     #[error("Unexpected EOF encountered")]
     UnexpectedEOF,
-    #[error("Expected '{0}'")]
-    Expected(char),
+    #[error("Expected '{0}' at {1}")]
+    Expected(char, Span),
+    #[error("Invalid escape sequence '{0}' at {1}")]
+    InvalidEscape(String, Span),
+    #[error("Unterminated block comment starting at {0}")]
+    UnterminatedComment(Span),
+}
+
+impl LexerError {
+    /// Returns the location of the error, if it has one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            LexerError::IntError(_, span)
+            | LexerError::FloatError(_, span)
+            | LexerError::InvalidChar(_, span)
+            | LexerError::Expected(_, span)
+            | LexerError::InvalidEscape(_, span)
+            | LexerError::UnterminatedComment(span) => Some(*span),
+            LexerError::UnexpectedEOF => None,
+        }
+    }
+
+    /// Moves the location of the error so that it is relative to `origin`. See `Span::offset`.
+    pub fn offset(self, origin: Span) -> Self {
+        match self {
+            LexerError::IntError(kind, span) => LexerError::IntError(kind, span.offset(origin)),
+            LexerError::FloatError(err, span) => LexerError::FloatError(err, span.offset(origin)),
+            LexerError::InvalidChar(c, span) => LexerError::InvalidChar(c, span.offset(origin)),
+            LexerError::Expected(c, span) => LexerError::Expected(c, span.offset(origin)),
+            LexerError::InvalidEscape(seq, span) => {
+                LexerError::InvalidEscape(seq, span.offset(origin))
+            }
+            LexerError::UnterminatedComment(span) => {
+                LexerError::UnterminatedComment(span.offset(origin))
+            }
+            LexerError::UnexpectedEOF => LexerError::UnexpectedEOF,
+        }
+    }
 }
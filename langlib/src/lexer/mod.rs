@@ -1,56 +1,133 @@
 pub mod err;
 pub mod op;
+pub mod span;
 pub mod tests;
 pub mod token;
 
+use unicode_ident::{is_xid_continue, is_xid_start};
+
+use crate::bigint::BigInt;
+
 use self::{
     err::LexerError,
     op::{BinOp, UnOp},
-    token::{Keyword, Token},
+    span::{advance_pos, Span},
+    token::{Keyword, SpannedToken, Token},
 };
 
 #[derive(Debug)]
 pub struct Lexer<'a> {
     input: &'a str,      // Source code
     pub position: usize, // Reading position
+    line: usize,         // Line of the reading position
+    col: usize,          // Column of the reading position
+    done: bool,          // Whether the end of the input or an error has been reached
+    recover: bool,       // Whether errors become error tokens instead of stopping the lexer
 }
 
 impl<'a> Lexer<'a> {
     /// Creates a new `Lexer` instance
-    pub fn new(input: &'a str) -> Lexer {
-        Self { input, position: 0 }
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Self {
+            input,
+            position: 0,
+            line: 1,
+            col: 1,
+            done: false,
+            recover: false,
+        }
     }
 
     /// Tokenizes a string
-    pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
-        let mut vec = Vec::new();
+    pub fn tokenize(&mut self) -> Result<Vec<SpannedToken>, LexerError> {
+        self.collect()
+    }
 
-        loop {
-            match self.next_token() {
-                Ok(token) => vec.push(token.0),
-                Err(err) => match err {
-                    LexerError::UnexpectedEOF => break,
-                    _ => return Err(err),
-                },
-            }
-        }
+    /// Tokenizes the whole string, collecting every error along the way instead of stopping at the first one. The
+    /// errors also appear in the tokens as `Token::Error`s where they happened.
+    pub fn tokenize_all(&mut self) -> (Vec<SpannedToken>, Vec<LexerError>) {
+        self.recover = true;
+
+        let mut errors = Vec::new();
 
-        Ok(vec)
+        let tokens = self
+            .filter_map(Result::ok)
+            .inspect(|token| {
+                if let Token::Error(err) = &token.token {
+                    errors.push(err.clone());
+                }
+            })
+            .collect();
+
+        (tokens, errors)
     }
 
-    /// Returns the next token with its length
-    fn next_token(&mut self) -> Result<(Token, usize), LexerError> {
-        self.skip_whitespace()?;
+    /// Returns the next token along with its location
+    fn next_token(&mut self) -> Result<SpannedToken, LexerError> {
+        // Comments can be followed by more whitespace and comments, so keep skipping until there's neither.
+        loop {
+            self.skip_whitespace()?;
+
+            let here = self.here();
+
+            match Lexer::comment_len(&self.input[self.position..])
+                .map_err(|err| err.offset(here))?
+            {
+                Some(len) => self.advance(len),
+                None => break,
+            }
+        }
 
         if self.input.len() - self.position == 0 {
             return Err(LexerError::UnexpectedEOF);
         }
 
-        let (token, len) = Lexer::parse_token(&self.input[self.position..])?;
+        let here = self.here();
 
-        self.position += len;
+        let (token, len) =
+            Lexer::parse_token(&self.input[self.position..]).map_err(|err| err.offset(here))?;
 
-        Ok((token, len))
+        self.advance(len);
+
+        Ok(SpannedToken::new(
+            token,
+            Span::new(here.start, self.position, here.line, here.col),
+        ))
+    }
+
+    /// Turns an error at the current reading position into an error token, skipping past whatever caused it.
+    fn recover_from(&mut self, err: LexerError) -> SpannedToken {
+        let here = self.here();
+        let rest = &self.input[self.position..];
+
+        let len = match &err {
+            // The error only points at the escape sequence, but the rest of the string has to be skipped too
+            LexerError::InvalidEscape(..) => Lexer::string_len(rest),
+            err => err.span().map_or(0, |span| span.end - here.start),
+        };
+
+        // Always move forward, so that the lexer can't get stuck on the same error
+        let len = len.max(rest.chars().next().map_or(0, char::len_utf8));
+
+        self.advance(len);
+
+        SpannedToken::new(
+            Token::Error(err),
+            Span::new(here.start, self.position, here.line, here.col),
+        )
+    }
+
+    /// Returns an empty span at the current reading position.
+    fn here(&self) -> Span {
+        Span::new(self.position, self.position, self.line, self.col)
+    }
+
+    /// Moves the reading position `len` bytes forward, keeping track of the line and column.
+    fn advance(&mut self, len: usize) {
+        let consumed = &self.input[self.position..self.position + len];
+
+        (self.line, self.col) = advance_pos(self.line, self.col, consumed);
+        self.position += len;
     }
 
     /// Attempts to parse a single token
@@ -61,10 +138,35 @@ impl<'a> Lexer<'a> {
         };
 
         match next {
+            '+' | '-' | '*' | '/' | '%' if Some('=') == data.chars().nth(1) => {
+                let op = match next {
+                    '+' => BinOp::Add,
+                    '-' => BinOp::Sub,
+                    '*' => BinOp::Mul,
+                    '/' => BinOp::Div,
+                    _ => BinOp::Mod,
+                };
+
+                Ok((Token::AssignOp(op), 2))
+            }
             '+' => Ok((Token::Op(BinOp::Add), 1)),
             '-' => Ok((Token::Op(BinOp::Sub), 1)),
-            '*' => Ok((Token::Op(BinOp::Mul), 1)),
+            '*' => {
+                if Some('*') == data.chars().nth(1) {
+                    Ok((Token::Op(BinOp::Pow), 2))
+                } else {
+                    Ok((Token::Op(BinOp::Mul), 1))
+                }
+            }
             '/' => Ok((Token::Op(BinOp::Div), 1)),
+            '%' => Ok((Token::Op(BinOp::Mod), 1)),
+            '~' => {
+                if Some('/') == data.chars().nth(1) {
+                    Ok((Token::Op(BinOp::FloorDiv), 2))
+                } else {
+                    Ok((Token::UnOp(UnOp::BitNot), 1))
+                }
+            }
             '=' => {
                 if Some('=') == data.chars().nth(1) {
                     Ok((Token::Op(BinOp::EqSign), 2))
@@ -77,8 +179,12 @@ impl<'a> Lexer<'a> {
             ')' => Ok((Token::RightBracket, 1)),
             '{' => Ok((Token::LeftCurly, 1)),
             '}' => Ok((Token::RightCurly, 1)),
+            '[' => Ok((Token::LeftSquare, 1)),
+            ']' => Ok((Token::RightSquare, 1)),
             ';' => Ok((Token::Semi, 1)),
             ',' => Ok((Token::Comma, 1)),
+            '?' => Ok((Token::Question, 1)),
+            ':' => Ok((Token::Colon, 1)),
             '!' => {
                 if Some('=') == data.chars().nth(1) {
                     Ok((Token::Op(BinOp::NeqSign), 2))
@@ -86,61 +192,179 @@ impl<'a> Lexer<'a> {
                     Ok((Token::UnOp(UnOp::Bang), 1))
                 }
             }
-            '>' => {
-                if Some('=') == data.chars().nth(1) {
-                    Ok((Token::Op(BinOp::GreaterEqSign), 2))
-                } else {
-                    Ok((Token::Op(BinOp::GreaterSign), 1))
-                }
-            }
-            '<' => {
-                if Some('=') == data.chars().nth(1) {
-                    Ok((Token::Op(BinOp::LessEqSign), 2))
-                } else {
-                    Ok((Token::Op(BinOp::LessSign), 1))
-                }
-            }
+            '>' => match data.chars().nth(1) {
+                Some('=') => Ok((Token::Op(BinOp::GreaterEqSign), 2)),
+                Some('>') => Ok((Token::Op(BinOp::Shr), 2)),
+                _ => Ok((Token::Op(BinOp::GreaterSign), 1)),
+            },
+            '<' => match data.chars().nth(1) {
+                Some('=') => Ok((Token::Op(BinOp::LessEqSign), 2)),
+                Some('<') => Ok((Token::Op(BinOp::Shl), 2)),
+                _ => Ok((Token::Op(BinOp::LessSign), 1)),
+            },
+            '&' => Ok((Token::Op(BinOp::BitAnd), 1)),
+            '|' => Ok((Token::Op(BinOp::BitOr), 1)),
+            '^' => Ok((Token::Op(BinOp::BitXor), 1)),
             '"' | '\'' => Lexer::tokenize_string(data),
             '0'..='9' => Lexer::tokenize_num(data),
+            '.' if data[1..].starts_with(|c: char| c.is_ascii_digit()) => Lexer::tokenize_num(data),
+            '.' => Ok((Token::Dot, 1)),
             _ => Lexer::tokenize_word(data),
         }
     }
 
     /// Attempts to tokenize a string
     fn tokenize_string(data: &str) -> Result<(Token, usize), LexerError> {
+        if data.starts_with("\"\"\"") {
+            return Lexer::tokenize_raw_string(data);
+        }
+
         let quote = match data.chars().next() {
             Some(c) => match c {
                 '\'' => '\'',
                 '"' => '"',
                 _ => {
-                    return Err(LexerError::InvalidChar(c));
+                    return Err(LexerError::InvalidChar(
+                        c,
+                        Span::locate(data, 0, c.len_utf8()),
+                    ));
                 }
             },
             None => return Err(LexerError::UnexpectedEOF),
         };
 
-        let (string, len) = Lexer::take_while(&data[1..], |c| c != quote)?;
+        let mut string = String::new();
+        let mut chars = data.char_indices().skip(1);
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                // Add 1 to the len for the closing quote
+                c if c == quote => return Ok((Token::String(string), idx + 1)),
+
+                '\\' => {
+                    let (escaped, len) = Lexer::parse_escape(&data[idx..])
+                        .map_err(|err| err.offset(Span::locate(data, idx, idx)))?;
+
+                    string.push(escaped);
+
+                    // Skip the rest of the escape sequence, the backslash itself has already been consumed
+                    chars.nth(data[idx..idx + len].chars().count() - 2);
+                }
+
+                c => string.push(c),
+            }
+        }
 
         // Case where no closing quote was found
-        if len == data.len() {
-            return Err(LexerError::Expected(quote));
+        Err(LexerError::Expected(
+            quote,
+            Span::locate(data, 0, data.len()),
+        ))
+    }
+
+    /// Attempts to tokenize a triple quoted string, which keeps everything between the quotes exactly as it's written.
+    fn tokenize_raw_string(data: &str) -> Result<(Token, usize), LexerError> {
+        match data[3..].find("\"\"\"") {
+            // Add 6 to the len for the quotes on both sides
+            Some(len) => Ok((Token::String(data[3..3 + len].to_owned()), len + 6)),
+            None => Err(LexerError::Expected('"', Span::locate(data, 0, data.len()))),
+        }
+    }
+
+    /// Returns the length of the string at the start of `data` without looking at what's inside of it, or the length
+    /// of `data` if the string is never closed.
+    fn string_len(data: &str) -> usize {
+        if let Some(rest) = data.strip_prefix("\"\"\"") {
+            return rest.find("\"\"\"").map_or(data.len(), |len| len + 6);
         }
 
-        // Add 2 to the len for some weird edge case
-        Ok((Token::String(string), len + 2))
+        let quote = match data.chars().next() {
+            Some(c) => c,
+            None => return 0,
+        };
+
+        let mut chars = data.char_indices().skip(1);
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                c if c == quote => return idx + 1,
+
+                // Whatever comes after a backslash can't close the string
+                '\\' => {
+                    chars.next();
+                }
+
+                _ => {}
+            }
+        }
+
+        data.len()
+    }
+
+    /// Attempts to parse the escape sequence at the start of `data`, returning the character and the length of the sequence.
+    fn parse_escape(data: &str) -> Result<(char, usize), LexerError> {
+        let invalid = |len: usize| {
+            LexerError::InvalidEscape(data[..len].to_owned(), Span::locate(data, 0, len))
+        };
+
+        let next = match data[1..].chars().next() {
+            Some(c) => c,
+            None => return Err(invalid(1)),
+        };
+
+        let escaped = match next {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+
+            // Unicode escapes look like `\u{1F600}`
+            'u' => {
+                if !data[2..].starts_with('{') {
+                    return Err(invalid(2));
+                }
+
+                let (digits, len) = Lexer::take_while(&data[3..], |c| c.is_ascii_hexdigit())?;
+
+                if !data[3 + len..].starts_with('}') {
+                    return Err(invalid(3 + len));
+                }
+
+                // Add 4 to the len for the `\u{` and the `}`
+                return match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    Some(c) if (1..=6).contains(&len) => Ok((c, len + 4)),
+                    _ => Err(invalid(len + 4)),
+                };
+            }
+
+            c => return Err(invalid(1 + c.len_utf8())),
+        };
+
+        Ok((escaped, 2))
     }
 
     /// Attempt to tokenize a "word", which could be an identifier or a keyword.
     fn tokenize_word(data: &str) -> Result<(Token, usize), LexerError> {
-        // Check if the word starts with valid character
+        // Check if the word starts with valid character, anything else that made it here isn't a valid token
         match data.chars().next() {
-            Some(ch) if ch.is_ascii_digit() => panic!("Identifiers can't start with a number"),
-            None => panic!(),
-            _ => {}
+            Some(ch) if ch == '_' || is_xid_start(ch) => {}
+            Some(ch) => {
+                return Err(LexerError::InvalidChar(
+                    ch,
+                    Span::locate(data, 0, ch.len_utf8()),
+                ))
+            }
+            None => return Err(LexerError::UnexpectedEOF),
         };
 
-        // take until we encounter a whitespace
-        let (word, len) = Lexer::take_while(data, |c| c.is_alphanumeric())?;
+        // take until we encounter a character that can't be part of an identifier
+        let (word, len) = Lexer::take_while(data, is_xid_continue)?;
 
         let word = match word.as_str() {
             "let" => Token::Keyword(Keyword::Let),
@@ -154,6 +378,12 @@ impl<'a> Lexer<'a> {
             "func" => Token::Keyword(Keyword::Func),
             "return" => Token::Keyword(Keyword::Return),
             "class" => Token::Keyword(Keyword::Class),
+            "null" => Token::Keyword(Keyword::Null),
+            "break" => Token::Keyword(Keyword::Break),
+            "continue" => Token::Keyword(Keyword::Continue),
+            "in" => Token::Keyword(Keyword::In),
+            "this" => Token::Keyword(Keyword::This),
+            "super" => Token::Keyword(Keyword::Super),
             "and" => Token::Op(BinOp::And),
             "or" => Token::Op(BinOp::Or),
             s => Token::Ident(s.to_owned()),
@@ -164,17 +394,54 @@ impl<'a> Lexer<'a> {
         Ok((word, len))
     }
 
-    /// Attemps to tokenize a number
+    /// Attemps to tokenize a number, which is a float if it has a fractional part or an exponent and an int otherwise.
     fn tokenize_num(data: &str) -> Result<(Token, usize), LexerError> {
-        // take_while will try to return
-        match Lexer::take_while(data, |c| c.is_ascii_digit()) {
-            // If it's ok we need to try to parse the number
-            Ok((num_string, len)) => match num_string.parse::<i32>() {
+        let digits = |from: usize| Lexer::take_while(&data[from..], |c| c.is_ascii_digit());
+
+        let (_, mut len) = digits(0)?;
+        let mut is_float = false;
+
+        // Fractional part, the dot has to be followed by a digit so that something like `1.` isn't a float
+        if data[len..].starts_with('.') {
+            let (_, frac_len) = digits(len + 1)?;
+
+            if frac_len > 0 {
+                len += frac_len + 1;
+                is_float = true;
+            }
+        }
+
+        // Exponent, which is only taken if it has at least one digit (e.g `1e-9`)
+        if data[len..].starts_with(['e', 'E']) {
+            let sign_len = usize::from(data[len + 1..].starts_with(['+', '-']));
+            let (_, exp_len) = digits(len + 1 + sign_len)?;
+
+            if exp_len > 0 {
+                len += exp_len + sign_len + 1;
+                is_float = true;
+            }
+        }
+
+        let num_string = &data[..len];
+
+        if is_float {
+            match num_string.parse::<f64>() {
+                Ok(num) => Ok((Token::Float(num), len)),
+                Err(err) => Err(LexerError::FloatError(err, Span::locate(data, 0, len))),
+            }
+        } else {
+            match num_string.parse::<i64>() {
                 Ok(num) => Ok((Token::Int(num), len)),
-                Err(err) => Err(LexerError::IntError(err.kind().to_owned())),
-            },
 
-            Err(error) => Err(error),
+                // Ints that don't fit in an `i64` become bignums instead
+                Err(err) => match num_string.parse::<BigInt>() {
+                    Ok(num) => Ok((Token::BigNum(num), len)),
+                    Err(_) => Err(LexerError::IntError(
+                        err.kind().to_owned(),
+                        Span::locate(data, 0, len),
+                    )),
+                },
+            }
         }
     }
 
@@ -185,13 +452,7 @@ impl<'a> Lexer<'a> {
     {
         let x = s
             .char_indices()
-            .find_map(|(idx, char)| {
-                if predicate(char) {
-                    return None;
-                }
-
-                Some(idx)
-            })
+            .find_map(|(idx, char)| if predicate(char) { None } else { Some(idx) })
             .unwrap_or(s.len());
 
         Ok((s[..x].to_owned(), x))
@@ -200,10 +461,10 @@ impl<'a> Lexer<'a> {
     /// If there are any whitespaces in the input, skip them by incrementing the `position` field.
     fn skip_whitespace(&mut self) -> Result<(), LexerError> {
         loop {
-            match self.input.chars().nth(self.position) {
+            match self.input[self.position..].chars().next() {
                 Some(c) => {
                     if c.is_whitespace() {
-                        self.position += 1;
+                        self.advance(c.len_utf8());
                     } else {
                         break;
                     }
@@ -214,4 +475,70 @@ impl<'a> Lexer<'a> {
 
         Ok(())
     }
+
+    /// Returns the length of the comment at the start of `data`, if there is one.
+    fn comment_len(data: &str) -> Result<Option<usize>, LexerError> {
+        // Line comments run until the end of the line, the newline itself is left for `skip_whitespace`
+        if data.starts_with("//") {
+            return Ok(Some(data.find('\n').unwrap_or(data.len())));
+        }
+
+        if !data.starts_with("/*") {
+            return Ok(None);
+        }
+
+        // Block comments can be nested, so we need to keep track of how deep we are
+        let mut depth = 0;
+        let mut idx = 0;
+
+        while idx < data.len() {
+            let rest = &data[idx..];
+
+            if rest.starts_with("/*") {
+                depth += 1;
+                idx += 2;
+            } else if rest.starts_with("*/") {
+                depth -= 1;
+                idx += 2;
+
+                if depth == 0 {
+                    return Ok(Some(idx));
+                }
+            } else {
+                idx += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+
+        Err(LexerError::UnterminatedComment(Span::locate(
+            data,
+            0,
+            data.len(),
+        )))
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Result<SpannedToken, LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_token() {
+            Ok(token) => Some(Ok(token)),
+            Err(LexerError::UnexpectedEOF) => {
+                self.done = true;
+                None
+            }
+
+            Err(err) if self.recover => Some(Ok(self.recover_from(err))),
+
+            // The lexer can't continue past an error, so it stops after reporting it
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 
 pub enum BinOp {
@@ -5,6 +7,9 @@ pub enum BinOp {
     Sub,
     Mul,
     Div,
+    Mod,
+    FloorDiv,
+    Pow,
     EqSign,
     NeqSign,
     GreaterSign,
@@ -13,6 +18,41 @@ pub enum BinOp {
     LessEqSign,
     And,
     Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+impl Display for BinOp {
+    /// Writes the operator the way it's written in the source code.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Mod => "%",
+            BinOp::FloorDiv => "~/",
+            BinOp::Pow => "**",
+            BinOp::EqSign => "==",
+            BinOp::NeqSign => "!=",
+            BinOp::GreaterSign => ">",
+            BinOp::LessSign => "<",
+            BinOp::GreaterEqSign => ">=",
+            BinOp::LessEqSign => "<=",
+            BinOp::And => "and",
+            BinOp::Or => "or",
+            BinOp::BitAnd => "&",
+            BinOp::BitOr => "|",
+            BinOp::BitXor => "^",
+            BinOp::Shl => "<<",
+            BinOp::Shr => ">>",
+        };
+
+        write!(f, "{op}")
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -20,4 +60,5 @@ pub enum BinOp {
 pub enum UnOp {
     Bang,
     Minus,
+    BitNot,
 }
@@ -0,0 +1,78 @@
+use std::fmt::Display;
+
+/// The location of a piece of source code.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Span {
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset one past the last character.
+    pub end: usize,
+    /// Line number of the first character, starting at 1.
+    pub line: usize,
+    /// Column of the first character counted in characters, starting at 1.
+    pub col: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, col: usize) -> Self {
+        Self {
+            start,
+            end,
+            line,
+            col,
+        }
+    }
+
+    /// Creates a span for the byte range `start..end` of `data`, with the line and column relative to the start of `data`.
+    pub fn locate(data: &str, start: usize, end: usize) -> Self {
+        let (line, col) = advance_pos(1, 1, &data[..start]);
+
+        Self::new(start, end, line, col)
+    }
+
+    /// Moves a span that is relative to `origin` so that it becomes relative to whatever `origin` is relative to.
+    pub fn offset(self, origin: Span) -> Self {
+        let col = if self.line == 1 {
+            origin.col + self.col - 1
+        } else {
+            self.col
+        };
+
+        Self::new(
+            origin.start + self.start,
+            origin.start + self.end,
+            origin.line + self.line - 1,
+            col,
+        )
+    }
+
+    /// The length of the span in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns a boolean indicating whether the span covers no characters at all.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// Returns the line and column reached after reading `consumed`, starting from `line` and `col`.
+pub fn advance_pos(mut line: usize, mut col: usize, consumed: &str) -> (usize, usize) {
+    for c in consumed.chars() {
+        if c == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+
+    (line, col)
+}
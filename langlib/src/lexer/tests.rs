@@ -1,8 +1,9 @@
 #[cfg(test)]
 mod lexer_tokenizer_tests {
-
     use crate::lexer::{
-        op::BinOp,
+        err::LexerError,
+        op::{BinOp, UnOp},
+        span::Span,
         token::{Keyword, Token},
         Lexer,
     };
@@ -29,11 +30,19 @@ mod lexer_tokenizer_tests {
 
         assert_eq!(num_token.0, Token::Int(123456789));
 
-        let overflow_num = "123456785435643829043568";
+        let big_num = "123456785435643829043568";
+
+        let big_num_token = Lexer::parse_token(big_num);
 
-        let overflow_num_token = Lexer::parse_token(overflow_num);
+        assert_eq!(
+            big_num_token,
+            Ok((Token::BigNum(big_num.parse().unwrap()), 24))
+        );
 
-        assert!(overflow_num_token.is_err());
+        // The biggest `i64` is still a regular int
+        let max_num_token = Lexer::parse_token("9223372036854775807");
+
+        assert_eq!(max_num_token, Ok((Token::Int(i64::MAX), 19)));
     }
 
     #[test]
@@ -128,4 +137,375 @@ mod lexer_tokenizer_tests {
         let result = lexer.tokenize();
         assert_eq!(result.unwrap(), vec![Token::Op(BinOp::LessEqSign)]);
     }
+
+    #[test]
+    fn test_spans() {
+        let s = "let a = 3;\n  print \"é\" + a;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let spans: Vec<Span> = tokens.iter().map(|token| token.span).collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(0, 3, 1, 1),
+                Span::new(4, 5, 1, 5),
+                Span::new(6, 7, 1, 7),
+                Span::new(8, 9, 1, 9),
+                Span::new(9, 10, 1, 10),
+                Span::new(13, 18, 2, 3),
+                Span::new(19, 23, 2, 9),
+                Span::new(24, 25, 2, 13),
+                Span::new(26, 27, 2, 15),
+                Span::new(27, 28, 2, 16),
+            ]
+        );
+
+        assert_eq!(&s[spans[6].start..spans[6].end], "\"é\"");
+    }
+
+    #[test]
+    fn test_error_location() {
+        let s = "let a = 3;\nlet b = \"unterminated;";
+
+        let result = Lexer::new(s).tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::Expected('"', Span::new(19, 33, 2, 9)))
+        );
+
+        let s = "let a = 3;\nlet b = 9 #;";
+
+        let result = Lexer::new(s).tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::InvalidChar('#', Span::new(21, 22, 2, 11)))
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let s = "let a = 3; // a comment with a / in it
+        // another comment
+        print a / 2; /* a block comment
+        that spans multiple lines /* and is nested */ */ print a;//";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::Let),
+                Token::Ident("a".to_owned()),
+                Token::DeclarationSign,
+                Token::Int(3),
+                Token::Semi,
+                Token::Keyword(Keyword::Print),
+                Token::Ident("a".to_owned()),
+                Token::Op(BinOp::Div),
+                Token::Int(2),
+                Token::Semi,
+                Token::Keyword(Keyword::Print),
+                Token::Ident("a".to_owned()),
+                Token::Semi,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_comment() {
+        let s = "let a = 3;\n/* /* nested */ but never closed";
+
+        let result = Lexer::new(s).tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::UnterminatedComment(Span::new(11, 43, 2, 1)))
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        let s = "3.25 1e-9 .5 2.5E+3 7 4e";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Float(3.25),
+                Token::Float(1e-9),
+                Token::Float(0.5),
+                Token::Float(2500.0),
+                Token::Int(7),
+                Token::Int(4),
+                Token::Ident("e".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let s = r#""a\tb\n\"quoted\" \\ \u{1F600}" 'it\'s' "#;
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String("a\tb\n\"quoted\" \\ 😀".to_owned()),
+                Token::String("it's".to_owned()),
+            ]
+        );
+
+        let result = Lexer::new(r#"let s = "bad \q escape";"#).tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::InvalidEscape(
+                "\\q".to_owned(),
+                Span::new(13, 15, 1, 14)
+            ))
+        );
+
+        let result = Lexer::new(r#""\u{110000}""#).tokenize();
+
+        assert!(matches!(result, Err(LexerError::InvalidEscape(..))));
+
+        let result = Lexer::new(r#""\u{1F600""#).tokenize();
+
+        assert!(matches!(result, Err(LexerError::InvalidEscape(..))));
+    }
+
+    #[test]
+    fn test_raw_strings() {
+        let s = "print \"\"\"first line\n  a \\n \"quoted\" line\"\"\";";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::Print),
+                Token::String("first line\n  a \\n \"quoted\" line".to_owned()),
+                Token::Semi,
+            ]
+        );
+
+        let result = Lexer::new("\"\"\"never closed\"").tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::Expected('"', Span::new(0, 16, 1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let s = "let my_var = _private + café + 变量2;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::Let),
+                Token::Ident("my_var".to_owned()),
+                Token::DeclarationSign,
+                Token::Ident("_private".to_owned()),
+                Token::Op(BinOp::Add),
+                Token::Ident("café".to_owned()),
+                Token::Op(BinOp::Add),
+                Token::Ident("变量2".to_owned()),
+                Token::Semi,
+            ]
+        );
+
+        let result = Lexer::new("let a = 1;\nlet #b = 2;").tokenize();
+
+        assert_eq!(
+            result,
+            Err(LexerError::InvalidChar('#', Span::new(15, 16, 2, 5)))
+        );
+
+        assert!(Lexer::new("a @ b").tokenize().is_err());
+        assert!(Lexer::new("a ` b").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lazy_iterator() {
+        let mut lexer = Lexer::new("let a = 3; #");
+
+        assert_eq!(lexer.next().unwrap().unwrap(), Token::Keyword(Keyword::Let));
+        assert_eq!(lexer.position, 3);
+
+        // Only the tokens that were asked for get lexed, so the error at the end hasn't been hit yet
+        let tokens: Vec<_> = lexer.by_ref().take(4).collect();
+
+        assert!(tokens.iter().all(Result::is_ok));
+        assert_eq!(lexer.position, 10);
+
+        assert!(matches!(
+            lexer.next(),
+            Some(Err(LexerError::InvalidChar('#', _)))
+        ));
+        assert!(lexer.next().is_none());
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        let s = "let a = #3;\nlet b = \"bad \\q \\\" escape\" + @;\nlet c = 99999999999`; print \"unterminated";
+
+        let (tokens, errors) = Lexer::new(s).tokenize_all();
+
+        assert_eq!(
+            errors,
+            vec![
+                LexerError::InvalidChar('#', Span::new(8, 9, 1, 9)),
+                LexerError::InvalidEscape("\\q".to_owned(), Span::new(25, 27, 2, 14)),
+                LexerError::InvalidChar('@', Span::new(41, 42, 2, 30)),
+                LexerError::InvalidChar('`', Span::new(63, 64, 3, 20)),
+                LexerError::Expected('"', Span::new(72, 85, 3, 29)),
+            ]
+        );
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::Let),
+                Token::Ident("a".to_owned()),
+                Token::DeclarationSign,
+                Token::Error(errors[0].clone()),
+                Token::Int(3),
+                Token::Semi,
+                Token::Keyword(Keyword::Let),
+                Token::Ident("b".to_owned()),
+                Token::DeclarationSign,
+                Token::Error(errors[1].clone()),
+                Token::Op(BinOp::Add),
+                Token::Error(errors[2].clone()),
+                Token::Semi,
+                Token::Keyword(Keyword::Let),
+                Token::Ident("c".to_owned()),
+                Token::DeclarationSign,
+                Token::Int(99999999999),
+                Token::Error(errors[3].clone()),
+                Token::Semi,
+                Token::Keyword(Keyword::Print),
+                Token::Error(errors[4].clone()),
+            ]
+        );
+
+        // The error token for the bad escape covers the whole string
+        assert_eq!(tokens[9].span, Span::new(20, 38, 2, 9));
+    }
+
+    #[test]
+    fn test_arithmetic_signs() {
+        let s = "a % b ** c ~/ d * e";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a".to_owned()),
+                Token::Op(BinOp::Mod),
+                Token::Ident("b".to_owned()),
+                Token::Op(BinOp::Pow),
+                Token::Ident("c".to_owned()),
+                Token::Op(BinOp::FloorDiv),
+                Token::Ident("d".to_owned()),
+                Token::Op(BinOp::Mul),
+                Token::Ident("e".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bitwise_signs() {
+        let s = "a & b | c ^ ~d << 2 >> 1 <= >=";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a".to_owned()),
+                Token::Op(BinOp::BitAnd),
+                Token::Ident("b".to_owned()),
+                Token::Op(BinOp::BitOr),
+                Token::Ident("c".to_owned()),
+                Token::Op(BinOp::BitXor),
+                Token::UnOp(UnOp::BitNot),
+                Token::Ident("d".to_owned()),
+                Token::Op(BinOp::Shl),
+                Token::Int(2),
+                Token::Op(BinOp::Shr),
+                Token::Int(1),
+                Token::Op(BinOp::LessEqSign),
+                Token::Op(BinOp::GreaterEqSign),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_signs() {
+        let s = "a += 1 -= *= /= %= ** = + =";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a".to_owned()),
+                Token::AssignOp(BinOp::Add),
+                Token::Int(1),
+                Token::AssignOp(BinOp::Sub),
+                Token::AssignOp(BinOp::Mul),
+                Token::AssignOp(BinOp::Div),
+                Token::AssignOp(BinOp::Mod),
+                Token::Op(BinOp::Pow),
+                Token::DeclarationSign,
+                Token::Op(BinOp::Add),
+                Token::DeclarationSign,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ternary_signs() {
+        let tokens = Lexer::new("a ? b : c").tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("a".to_owned()),
+                Token::Question,
+                Token::Ident("b".to_owned()),
+                Token::Colon,
+                Token::Ident("c".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_property_access() {
+        let tokens = Lexer::new("this.a.b .5").tokenize().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Keyword(Keyword::This),
+                Token::Dot,
+                Token::Ident("a".to_owned()),
+                Token::Dot,
+                Token::Ident("b".to_owned()),
+                Token::Float(0.5),
+            ]
+        );
+    }
 }
@@ -1,25 +1,41 @@
 use thiserror::Error;
 
-use crate::expr::Expr;
+use crate::{bigint::BigInt, expr::Expr};
 
-use super::op::{BinOp, UnOp};
+use super::{
+    err::LexerError,
+    op::{BinOp, UnOp},
+    span::Span,
+};
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Op(BinOp),
     UnOp(UnOp),
     DeclarationSign,
+    /// An assignment that applies an operator to the old value first, such as `+=`.
+    AssignOp(BinOp),
     EqSign,
-    Int(i32),
+    Int(i64),
+    /// An int literal that's too big for an `i64`.
+    BigNum(BigInt),
+    Float(f64),
     Semi,
     Comma,
+    Dot,
+    Question,
+    Colon,
     String(String),
     LeftBracket,
     RightBracket,
     LeftCurly,
     RightCurly,
+    LeftSquare,
+    RightSquare,
     Ident(String),
     Keyword(Keyword),
+    /// A piece of source code that couldn't be lexed, only produced by a lexer that recovers from errors.
+    Error(LexerError),
 }
 
 impl Token {
@@ -31,7 +47,7 @@ impl Token {
         }
     }
 
-    pub fn try_into_int(self) -> Result<i32, TokenError> {
+    pub fn try_into_int(self) -> Result<i64, TokenError> {
         if let Token::Int(int) = self {
             Ok(int)
         } else {
@@ -66,6 +82,8 @@ impl Token {
     pub fn into_expr(self) -> Result<Expr, TokenError> {
         match self {
             Token::Int(int) => Ok(Expr::Num(int)),
+            Token::BigNum(num) => Ok(Expr::BigNum(num)),
+            Token::Float(float) => Ok(Expr::Float(float)),
             Token::String(string) => Ok(Expr::Str(string)),
             Token::Keyword(keyword) => match keyword {
                 Keyword::True => Ok(Expr::Bool(true)),
@@ -77,6 +95,32 @@ impl Token {
     }
 }
 
+/// A token together with the location it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span }
+    }
+}
+
+impl From<Token> for SpannedToken {
+    /// Wraps a token which doesn't come from any source code, giving it an empty span.
+    fn from(token: Token) -> Self {
+        Self::new(token, Span::default())
+    }
+}
+
+impl PartialEq<Token> for SpannedToken {
+    fn eq(&self, other: &Token) -> bool {
+        &self.token == other
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Error)]
 pub enum TokenError {
     #[error("An invalid token conversion was attemped.")]
@@ -97,10 +141,15 @@ pub enum Keyword {
     Func,
     Return,
     Class,
+    Null,
+    Break,
+    Continue,
+    In,
+    This,
+    Super,
 }
 
 #[cfg(test)]
-
 mod token_tests {
     use crate::{
         expr::Expr,
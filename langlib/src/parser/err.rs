@@ -2,16 +2,22 @@ use thiserror::Error;
 
 use crate::{
     expr::ExprError,
-    lexer::token::{Token, TokenError},
+    lexer::{
+        err::LexerError,
+        token::{Keyword, Token, TokenError},
+    },
     stmt::StmtErr,
 };
 
-#[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[derive(Debug, Clone, PartialEq, Error)]
 /// Error enum for the `Parser` struct.
 pub enum ParserError {
     #[error("An invalid or incomplete let statement was encountered")]
     InvalidLetStatement(usize),
 
+    #[error("A lexer error has occured while parsing: {0}")]
+    LexerError(#[from] LexerError),
+
     #[error("A token error has occured while parsing")]
     TokenError(#[from] TokenError),
 
@@ -53,4 +59,10 @@ pub enum ParserError {
 
     #[error("The parser failed to match the rule: {0:?}")]
     FailedRuleMatch(Vec<Token>, usize),
+
+    #[error("A {0:?} statement was found outside of a loop.")]
+    OutsideLoop(Keyword, usize),
+
+    #[error("Only variables, list elements and properties can be assigned to.")]
+    InvalidAssignmentTarget(usize),
 }
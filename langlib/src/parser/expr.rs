@@ -2,16 +2,37 @@ use crate::{
     expr::{BinExpr, Expr},
     lexer::{
         op::{BinOp, UnOp},
-        token::Token,
+        token::{Keyword, Token},
     },
 };
 
 use super::{err::ParserError, Parser};
 
-impl Parser {
+impl Parser<'_> {
     /// Attempts to parse an expression.
     pub fn expr(&mut self) -> Result<Expr, ParserError> {
-        self.logical_or()
+        self.ternary()
+    }
+
+    /// Attempts to parse a `condition ? then : otherwise` expression. Nested ternaries group to the right.
+    pub fn ternary(&mut self) -> Result<Expr, ParserError> {
+        let condition = self.logical_or()?;
+
+        if self.matches(&[Token::Question]).is_none() {
+            return Ok(condition);
+        }
+
+        let then = self.expr()?;
+
+        self.expect_consume(&[Token::Colon])?;
+
+        let otherwise = self.ternary()?;
+
+        Ok(Expr::Ternary(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
     }
 
     /// Attempts to parse a logical `or` expession.
@@ -33,9 +54,60 @@ impl Parser {
 
     /// Attempts to parse a logical `and` expession.
     pub fn logical_and(&mut self) -> Result<Expr, ParserError> {
-        let mut lhs = self.equality()?;
+        let mut lhs = self.bit_or()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::And)]) {
+            let rhs = self.bit_or()?;
+
+            lhs = Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: op.try_into_op()?,
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    /// Attempts to parse a bitwise `|` expression.
+    pub fn bit_or(&mut self) -> Result<Expr, ParserError> {
+        let mut lhs = self.bit_xor()?;
+
+        while let Some(op) = self.matches(&[Token::Op(BinOp::BitOr)]) {
+            let rhs = self.bit_xor()?;
+
+            lhs = Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: op.try_into_op()?,
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    /// Attempts to parse a bitwise `^` expression.
+    pub fn bit_xor(&mut self) -> Result<Expr, ParserError> {
+        let mut lhs = self.bit_and()?;
+
+        while let Some(op) = self.matches(&[Token::Op(BinOp::BitXor)]) {
+            let rhs = self.bit_and()?;
+
+            lhs = Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: op.try_into_op()?,
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    /// Attempts to parse a bitwise `&` expression. Like in C, it binds looser than comparisons.
+    pub fn bit_and(&mut self) -> Result<Expr, ParserError> {
+        let mut lhs = self.equality()?;
+
+        while let Some(op) = self.matches(&[Token::Op(BinOp::BitAnd)]) {
             let rhs = self.equality()?;
 
             lhs = Expr::Bin(BinExpr {
@@ -67,7 +139,7 @@ impl Parser {
 
     /// Attempts to parse a comparision expression.
     pub fn comparision(&mut self) -> Result<Expr, ParserError> {
-        let mut lhs = self.term()?;
+        let mut lhs = self.shift()?;
 
         while let Some(op) = self.matches(&[
             Token::Op(BinOp::GreaterSign),
@@ -75,6 +147,23 @@ impl Parser {
             Token::Op(BinOp::LessSign),
             Token::Op(BinOp::LessEqSign),
         ]) {
+            let rhs = self.shift()?;
+
+            lhs = Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: op.try_into_op()?,
+            });
+        }
+
+        Ok(lhs)
+    }
+
+    /// Attempts to parse a bit shift expression.
+    pub fn shift(&mut self) -> Result<Expr, ParserError> {
+        let mut lhs = self.term()?;
+
+        while let Some(op) = self.matches(&[Token::Op(BinOp::Shl), Token::Op(BinOp::Shr)]) {
             let rhs = self.term()?;
 
             lhs = Expr::Bin(BinExpr {
@@ -108,7 +197,12 @@ impl Parser {
     pub fn factor(&mut self) -> Result<Expr, ParserError> {
         let mut lhs = self.unary()?;
 
-        while let Some(op) = self.matches(&[Token::Op(BinOp::Mul), Token::Op(BinOp::Div)]) {
+        while let Some(op) = self.matches(&[
+            Token::Op(BinOp::Mul),
+            Token::Op(BinOp::Div),
+            Token::Op(BinOp::Mod),
+            Token::Op(BinOp::FloorDiv),
+        ]) {
             let rhs = self.unary()?;
 
             lhs = Expr::Bin(BinExpr {
@@ -121,23 +215,62 @@ impl Parser {
         Ok(lhs)
     }
 
-    /// Attempts to parse a unary expression.
+    /// Attempts to parse a unary expression. Unary operators can be chained, like in `!!x` or `- -x`.
     pub fn unary(&mut self) -> Result<Expr, ParserError> {
-        if self.match_rule(&[Token::UnOp(UnOp::Bang)]) {
-            let expr = self.primary()?;
-            return Ok(Expr::Unary(UnOp::Bang, Box::new(expr)));
+        if let Some(op) = self.matches(&[
+            Token::UnOp(UnOp::Bang),
+            Token::UnOp(UnOp::BitNot),
+            Token::Op(BinOp::Sub),
+        ]) {
+            // The lexer can't tell a minus sign apart from a subtraction, so that's decided here
+            let op = match op {
+                Token::Op(BinOp::Sub) => UnOp::Minus,
+                op => op.try_into_un_op()?,
+            };
+
+            let expr = self.unary()?;
+            return Ok(Expr::Unary(op, Box::new(expr)));
         }
 
-        self.funcall()
+        self.power()
     }
 
-    /// Attempts to parse a function call.
+    /// Attempts to parse an exponentiation. It binds tighter than unary operators on its left (`-2 ** 2` is `-4`) and is
+    /// right associative (`2 ** 3 ** 2` is `2 ** 9`).
+    pub fn power(&mut self) -> Result<Expr, ParserError> {
+        let lhs = self.funcall()?;
+
+        if self.match_rule(&[Token::Op(BinOp::Pow)]) {
+            let rhs = self.unary()?;
+
+            return Ok(Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: BinOp::Pow,
+            }));
+        }
+
+        Ok(lhs)
+    }
+
+    /// Attempts to parse a function call, as well as indexing such as `a[i]` and property access such as `a.b`.
     pub fn funcall(&mut self) -> Result<Expr, ParserError> {
         let mut expr = self.primary()?;
 
         loop {
             if self.match_rule(&[Token::LeftBracket]) {
                 expr = self.parse_args(expr)?;
+            } else if self.match_rule(&[Token::LeftSquare]) {
+                let index = self.expr()?;
+
+                self.expect_consume(&[Token::RightSquare])?;
+
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else if self.match_rule(&[Token::Dot]) {
+                let name = self.curr()?.try_into_ident()?;
+                self.adv();
+
+                expr = Expr::Get(Box::new(expr), name);
             } else {
                 break;
             }
@@ -176,6 +309,14 @@ impl Parser {
                 self.adv();
                 Ok(Expr::Num(int))
             }
+            Token::BigNum(num) => {
+                self.adv();
+                Ok(Expr::BigNum(num))
+            }
+            Token::Float(float) => {
+                self.adv();
+                Ok(Expr::Float(float))
+            }
             Token::String(str) => {
                 self.adv();
 
@@ -187,22 +328,92 @@ impl Parser {
                 Ok(Expr::Var(ident))
             }
             Token::Keyword(keyword) => match keyword {
-                crate::lexer::token::Keyword::True => {
+                Keyword::True => {
                     self.adv();
 
                     Ok(Expr::Bool(true))
                 }
-                crate::lexer::token::Keyword::False => {
+                Keyword::False => {
                     self.adv();
 
                     Ok(Expr::Bool(false))
                 }
+                Keyword::Null => {
+                    self.adv();
+
+                    Ok(Expr::Null)
+                }
+
+                Keyword::This => {
+                    self.adv();
+
+                    Ok(Expr::This)
+                }
+                Keyword::Super => {
+                    self.adv();
+
+                    self.expect_consume(&[Token::Dot])?;
+
+                    let method = self.curr()?.try_into_ident()?;
+                    self.adv();
+
+                    Ok(Expr::Super(method))
+                }
+
+                // An anonymous function such as `func (a, b) { ... }`
+                Keyword::Func => {
+                    self.adv();
+
+                    Ok(Expr::Func(self.func_body()?))
+                }
                 _ => Err(ParserError::UnexpectedToken(
                     Token::Keyword(keyword),
                     self.cursor,
                 )),
             },
 
+            // A list literal such as `[1, 2, 3]`
+            Token::LeftSquare => {
+                self.adv();
+
+                let mut items = Vec::new();
+
+                while self.curr()? != Token::RightSquare {
+                    items.push(self.expr()?);
+
+                    if !self.match_rule(&[Token::Comma]) {
+                        break;
+                    }
+                }
+
+                self.expect_consume(&[Token::RightSquare])?;
+
+                Ok(Expr::List(items))
+            }
+
+            // A map literal such as `{"k": v}`
+            Token::LeftCurly => {
+                self.adv();
+
+                let mut entries = Vec::new();
+
+                while self.curr()? != Token::RightCurly {
+                    let key = self.expr()?;
+
+                    self.expect_consume(&[Token::Colon])?;
+
+                    entries.push((key, self.expr()?));
+
+                    if !self.match_rule(&[Token::Comma]) {
+                        break;
+                    }
+                }
+
+                self.expect_consume(&[Token::RightCurly])?;
+
+                Ok(Expr::Map(entries))
+            }
+
             // Attempt to parse an expression wrapped in brackets
             _ => {
                 self.expect_consume(&[Token::LeftBracket])?;
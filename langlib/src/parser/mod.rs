@@ -3,23 +3,69 @@ mod expr;
 mod stmt;
 mod tests;
 
+use std::iter;
+
 use crate::stmt::Stmt;
 
 use self::err::ParserError;
 
-use super::lexer::token::Token;
-
-pub struct Parser {
-    tokens: Vec<Token>,
+use super::lexer::{
+    err::LexerError,
+    token::{SpannedToken, Token},
+    Lexer,
+};
+
+pub struct Parser<'a> {
+    /// Where tokens that haven't been read yet come from.
+    source: Box<dyn Iterator<Item = Result<SpannedToken, LexerError>> + 'a>,
+    /// The tokens that have been read but not dropped yet, starting from the one at index `dropped`.
+    tokens: Vec<SpannedToken>,
+    /// The number of tokens that were dropped once the statements they belong to were parsed. The cursor and the
+    /// indices of tokens still count from the start of the source.
+    dropped: usize,
     cursor: usize,
+    /// The error that cut the token source short, if there was one.
+    lexer_error: Option<LexerError>,
+    /// How many loops the statement being parsed is nested in, so that `break` and `continue` can be rejected outside
+    /// of one.
+    loop_depth: usize,
 }
 
-impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, cursor: 0 }
+impl<'a> Parser<'a> {
+    pub fn new<T: Into<SpannedToken>>(tokens: Vec<T>) -> Self {
+        Self {
+            source: Box::new(iter::empty()),
+            tokens: tokens.into_iter().map(Into::into).collect(),
+            dropped: 0,
+            cursor: 0,
+            lexer_error: None,
+            loop_depth: 0,
+        }
+    }
+
+    /// Creates a parser which pulls tokens out of the lexer as they are needed.
+    pub fn from_lexer(lexer: Lexer<'a>) -> Self {
+        Self {
+            source: Box::new(lexer),
+            tokens: Vec::new(),
+            dropped: 0,
+            cursor: 0,
+            lexer_error: None,
+            loop_depth: 0,
+        }
     }
 
     pub fn get_statements(&mut self) -> Result<Vec<Stmt>, ParserError> {
+        let result = self.statements();
+
+        // A lexer error ends the stream of tokens early, so it's the actual cause of whatever happened after it
+        match self.lexer_error.take() {
+            Some(err) => Err(ParserError::LexerError(err)),
+            None => result,
+        }
+    }
+
+    fn statements(&mut self) -> Result<Vec<Stmt>, ParserError> {
         let mut stmt_vec = Vec::new();
 
         let stmt = self.stmt()?;
@@ -27,6 +73,10 @@ impl Parser {
         stmt_vec.push(stmt);
 
         while !self.is_at_end() {
+            // Nothing goes back to an earlier top-level statement, so the tokens of the previous ones can be dropped
+            // to keep memory use flat for long scripts
+            self.drop_parsed();
+
             let stmt = self.stmt()?;
 
             stmt_vec.push(stmt);
@@ -43,7 +93,7 @@ impl Parser {
         match possible_tokens.iter().find_map(|token| {
             // Return the token if the current token matches
 
-            if (!self.is_at_end()) && (&self.tokens[self.cursor] == token) {
+            if (!self.is_at_end()) && (self.get(self.cursor) == Some(token)) {
                 self.adv();
                 return Some(token.to_owned());
             }
@@ -75,7 +125,7 @@ impl Parser {
             // Check if an int token is the current rule
             if let Token::Int(_) = token {
                 // Check if the token matches the current rule
-                if let Token::Int(_) = self.tokens[self.cursor] {
+                if let Some(Token::Int(_)) = self.get(self.cursor) {
                     self.adv();
                     return true;
                 }
@@ -88,7 +138,7 @@ impl Parser {
             }
 
             // Match the rest of the tokens
-            if &self.tokens[self.cursor] == token {
+            if self.get(self.cursor) == Some(token) {
                 self.adv();
                 return true;
             }
@@ -112,41 +162,76 @@ impl Parser {
     /// Increments the `pos` field
     pub fn adv(&mut self) {
         self.cursor += 1;
-
-        if self.curr() == Ok(Token::Semi) && !self.is_at_end() {
-            self.cursor += 1;
-        }
     }
 
     /// Returns the previous token
-    fn prev(&self) -> Result<Token, ParserError> {
-        if self.cursor == 0 {
-            return Err(ParserError::InvalidTokenIndex(self.cursor));
-        }
-
-        Ok(self.tokens[self.cursor - 1].to_owned())
+    #[cfg(test)]
+    fn prev(&mut self) -> Result<Token, ParserError> {
+        self.cursor
+            .checked_sub(1)
+            .and_then(|i| self.get(i))
+            .cloned()
+            .ok_or(ParserError::InvalidTokenIndex(self.cursor))
     }
 
     /// Returns the current token, if there is one.
-    fn curr(&self) -> Result<Token, ParserError> {
+    fn curr(&mut self) -> Result<Token, ParserError> {
         if self.is_at_end() {
-            return Err(ParserError::UnexpectedEOF);
+            return match &self.lexer_error {
+                Some(err) => Err(ParserError::LexerError(err.clone())),
+                None => Err(ParserError::UnexpectedEOF),
+            };
         }
-        Ok(self.tokens[self.cursor].to_owned())
+        self.get(self.cursor)
+            .cloned()
+            .ok_or(ParserError::InvalidTokenIndex(self.cursor))
     }
 
     /// Returns the token at the given index `i`
-    fn at(&self, i: usize) -> Result<Token, ParserError> {
-        if i >= self.tokens.len() {
+    fn at(&mut self, i: usize) -> Result<Token, ParserError> {
+        if !self.fill(i) {
             return Err(ParserError::InvalidTokenIndex(i));
         }
 
-        Ok(self.tokens[i].to_owned())
+        self.get(i)
+            .cloned()
+            .ok_or(ParserError::InvalidTokenIndex(i))
+    }
+
+    /// Returns the token at the given index `i` if it has been read and not dropped, without reading any more tokens.
+    fn get(&self, i: usize) -> Option<&Token> {
+        let spanned = self.tokens.get(i.checked_sub(self.dropped)?)?;
+
+        Some(&spanned.token)
+    }
+
+    /// Drops the tokens before the cursor, which can't be read anymore afterwards.
+    fn drop_parsed(&mut self) {
+        self.tokens.drain(..self.cursor - self.dropped);
+        self.dropped = self.cursor;
     }
 
     /// Returns a boolean indicating whether the position is at the end of the token stream.
-    pub fn is_at_end(&self) -> bool {
-        self.cursor >= self.tokens.len()
+    pub fn is_at_end(&mut self) -> bool {
+        !self.fill(self.cursor)
+    }
+
+    /// Reads tokens from the source until the token at index `i` is available. Returns `false` if the source ran out
+    /// before that.
+    fn fill(&mut self, i: usize) -> bool {
+        while self.dropped + self.tokens.len() <= i && self.lexer_error.is_none() {
+            match self.source.next() {
+                Some(Ok(SpannedToken {
+                    token: Token::Error(err),
+                    ..
+                })) => self.lexer_error = Some(err),
+                Some(Ok(token)) => self.tokens.push(token),
+                Some(Err(err)) => self.lexer_error = Some(err),
+                None => break,
+            }
+        }
+
+        i < self.dropped + self.tokens.len()
     }
 
     pub fn expect_consume(&mut self, tokens: &[Token]) -> Result<(), ParserError> {
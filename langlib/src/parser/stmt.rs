@@ -1,90 +1,110 @@
 use super::{err::ParserError, Parser};
 use crate::{
-    expr::Expr,
+    expr::{BinExpr, Expr},
     func::Func,
-    lexer::token::{Keyword, Token},
-    stmt::{Declaration, Stmt},
+    lexer::{
+        op::BinOp,
+        token::{Keyword, Token},
+    },
+    stmt::{ClassDecl, Declaration, ForIn, IndexAssignment, PropertyAssignment, Stmt},
 };
 
-impl Parser {
-    /// Attempts to parse a statement from the next tokens until it encounters a semicolon.
+impl Parser<'_> {
+    /// Attempts to parse a statement, deciding what kind of statement it is from its first token.
     pub fn stmt(&mut self) -> Result<Stmt, ParserError> {
-        // The index of the next semicolon.
-        let idx = match (self.cursor..self.tokens.len())
-            .into_iter()
-            .find(|i| self.at(*i).unwrap() == Token::Semi)
-        {
-            Some(idx) => idx,
-            None => {
-                return Err(ParserError::Expected(
-                    Token::Semi,
-                    self.curr()?,
-                    self.cursor,
-                ))
+        match self.curr()? {
+            // Statements that end with a block don't need a semicolon
+            Token::Keyword(Keyword::If) => self.if_stmt(),
+            Token::Keyword(Keyword::While) => self.while_stmt(),
+            Token::Keyword(Keyword::For) => self.for_stmt(),
+            Token::Keyword(Keyword::Func) => self.func(),
+            Token::Keyword(Keyword::Class) => self.class_stmt(),
+            Token::LeftCurly if !self.starts_map() => self.block(),
+
+            // Everything else has to end with one
+            _ => {
+                let stmt = self.simple_stmt()?;
+                self.expect_consume(&[Token::Semi])?;
+
+                Ok(stmt)
             }
-        };
+        }
+    }
 
-        // Tokens of the statement
-        let slice = &self.tokens[self.cursor..idx];
-
-        // Big ugly match expression that's very difficult to read
-        match slice.iter().next() {
-            // Check if there's even a token
-            Some(token) => match token {
-                // Match the keyword
-                Token::Keyword(keyword) => match keyword {
-                    // If it's an Declaration statement
-                    Keyword::Let => self.declaration(),
-                    Keyword::Print => self.print(),
-                    Keyword::If => self.if_stmt(),
-                    Keyword::While => self.while_stmt(),
-                    Keyword::For => self.for_stmt(),
-                    Keyword::Func => self.func(),
-                    Keyword::Return => self.return_stmt(),
-
-                    _ => Err(ParserError::BadStatement(self.cursor)),
-                },
+    /// Attempts to parse a statement that's ended by a semicolon, without the semicolon itself. That's also what the
+    /// increment of a for loop is.
+    fn simple_stmt(&mut self) -> Result<Stmt, ParserError> {
+        match self.curr()? {
+            // Match the keyword
+            Token::Keyword(keyword) => match keyword {
+                // If it's an Declaration statement
+                Keyword::Let => self.declaration(),
+                Keyword::Print => self.print(),
+                Keyword::Return => self.return_stmt(),
+                Keyword::This | Keyword::Super => self.assignment(),
+                Keyword::Break | Keyword::Continue => self.loop_control(keyword),
+
+                _ => Err(ParserError::BadStatement(self.cursor)),
+            },
 
-                Token::LeftCurly => self.block(),
-                Token::Ident(_) => {
-                    if self.at(self.cursor + 1)? != Token::LeftBracket {
-                        self.assignment()
-                    } else {
-                        Ok(Stmt::Expr(self.expr()?))
-                    }
-                }
+            Token::Ident(_) => self.assignment(),
 
-                // Attempt to parse an expression statement
-                _ => match self.expr() {
-                    Ok(expr) => Ok(Stmt::Expr(expr)),
-                    Err(err) => Err(err),
-                },
-            },
+            // Attempt to parse an expression statement, which includes a map literal such as `{"a": 1}["a"];`
+            _ => Ok(Stmt::Expr(self.expr()?)),
+        }
+    }
 
-            // Reset position
-            None => Err(ParserError::UnexpectedEOF),
+    /// Checks whether the `{` at the cursor starts a map literal rather than a block, without moving the cursor. It
+    /// does when its first key is followed by a `:`, or when it's an empty `{}` that's indexed or ends the statement.
+    fn starts_map(&mut self) -> bool {
+        let start = self.cursor;
+
+        if self.at(start + 1) == Ok(Token::RightCurly) {
+            return matches!(self.at(start + 2), Ok(Token::Semi | Token::LeftSquare));
         }
+
+        // The key can be any expression, such as `{"a" + "b": 1}`. Instead of parsing it, the tokens are scanned for a
+        // `:` outside of any brackets and ternaries, which stops at the end of the first statement if it's a block
+        let mut depth = 0;
+        let mut ternaries = 0;
+
+        for i in start + 1.. {
+            match self.at(i) {
+                Ok(Token::LeftBracket | Token::LeftSquare | Token::LeftCurly) => depth += 1,
+                Ok(Token::RightBracket | Token::RightSquare | Token::RightCurly) if depth > 0 => {
+                    depth -= 1
+                }
+                Ok(Token::Question) if depth == 0 => ternaries += 1,
+                Ok(Token::Colon) if depth == 0 && ternaries > 0 => ternaries -= 1,
+                Ok(Token::Colon) if depth == 0 => return true,
+                Ok(Token::Semi | Token::RightBracket | Token::RightSquare | Token::RightCurly)
+                    if depth == 0 =>
+                {
+                    return false
+                }
+                Ok(_) => {}
+                Err(_) => return false,
+            }
+        }
+
+        false
     }
 
     /// Attempts to parse a declaration statement.
     fn declaration(&mut self) -> Result<Stmt, ParserError> {
-        self.expect_consume(&[Token::Keyword(Keyword::Let), Token::Ident(String::from(""))])?;
-        if self.match_rule(&[Token::DeclarationSign]) {
-            // Get the identier and value
-            let ident = self.at(self.cursor - 2)?.try_into_ident()?;
-
-            let expr = self.expr()?;
+        self.expect_consume(&[Token::Keyword(Keyword::Let)])?;
 
-            return Ok(Stmt::Declaration(Declaration { ident, val: expr }));
-        }
+        // Get the identifier and advance.
+        let ident = self.curr()?.try_into_ident()?;
+        self.adv();
 
-        let ident = self.at(self.cursor - 2)?.try_into_ident()?;
+        // Set the variable to null by default
+        let val = match self.match_rule(&[Token::DeclarationSign]) {
+            true => self.expr()?,
+            false => Expr::Null,
+        };
 
-        // Set the variable to null by default;
-        Ok(Stmt::Declaration(Declaration {
-            ident,
-            val: Expr::Null,
-        }))
+        Ok(Stmt::Declaration(Declaration { ident, val }))
     }
 
     /// Attempts to parse a print statement.
@@ -138,21 +158,23 @@ impl Parser {
             ));
         }
 
-        // If we execute the block inside then that means we may have reached a line of code that looks like:
-        // if (condition) statement;
-        if self.curr()? != Token::LeftCurly {
-            let stmt = self.stmt()?;
-
-            return Ok(Stmt::If(expr, Box::new(stmt), None));
-        }
-
-        let block = self.block()?;
+        let body = self.body()?;
 
+        // An `else if` chain is just an if statement nested in the else branch
         if self.match_rule(&[Token::Keyword(Keyword::Else)]) {
-            let else_block = self.block()?;
-            Ok(Stmt::If(expr, Box::new(block), Some(Box::new(else_block))))
+            let else_body = self.body()?;
+            Ok(Stmt::If(expr, Box::new(body), Some(Box::new(else_body))))
         } else {
-            Ok(Stmt::If(expr, Box::new(block), None))
+            Ok(Stmt::If(expr, Box::new(body), None))
+        }
+    }
+
+    /// Attempts to parse the body of an if statement or a loop, which is either a block or a single statement such as
+    /// `if (condition) statement;`
+    fn body(&mut self) -> Result<Stmt, ParserError> {
+        match self.curr()? {
+            Token::LeftCurly => self.block(),
+            _ => self.stmt(),
         }
     }
 
@@ -164,51 +186,124 @@ impl Parser {
 
         self.expect_consume(&[Token::RightBracket])?;
 
-        let block = self.block()?;
+        let body = self.loop_body()?;
 
-        Ok(Stmt::While(expr, Box::new(block)))
+        Ok(Stmt::While(expr, Box::new(body)))
     }
 
-    /// Attempts to parse an assignment.
-    fn assignment(&mut self) -> Result<Stmt, ParserError> {
-        self.expect_consume(&[Token::Ident(String::from(""))])?;
+    /// Attempts to parse the body of a loop, in which `break` and `continue` are allowed.
+    fn loop_body(&mut self) -> Result<Stmt, ParserError> {
+        self.loop_depth += 1;
+        let body = self.body();
+        self.loop_depth -= 1;
 
-        let ident = self.prev()?;
+        body
+    }
 
-        self.expect_consume(&[Token::DeclarationSign])?;
+    /// Attempts to parse an assignment such as `a = 1`, `a += 1`, `a[0] = 1` or `a.b = 1`. If there's no assignment sign after
+    /// the target then it's parsed as an expression statement instead, like `f(a);`.
+    fn assignment(&mut self) -> Result<Stmt, ParserError> {
+        let target_pos = self.cursor;
+        let target = self.expr()?;
 
-        let expr = self.expr()?;
+        let op = match self.curr() {
+            Ok(Token::DeclarationSign) => None,
+            Ok(Token::AssignOp(op)) => Some(op),
+            _ => return Ok(Stmt::Expr(target)),
+        };
 
-        Ok(Stmt::Assignment(Declaration {
-            ident: ident.try_into_ident()?,
-            val: expr,
-        }))
+        self.adv();
+
+        let val = self.expr()?;
+
+        match target {
+            // Compound assignments such as `a += 1` are desugared into `a = a + 1`
+            Expr::Var(ident) => Ok(Stmt::Assignment(Declaration {
+                val: match op {
+                    Some(op) => Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Var(ident.clone())),
+                        rhs: Box::new(val),
+                        op,
+                    }),
+                    None => val,
+                },
+                ident,
+            })),
+            Expr::Index(target, index) => Ok(Stmt::IndexAssignment(IndexAssignment {
+                target: *target,
+                index: *index,
+                op,
+                val,
+            })),
+            Expr::Get(target, name) => Ok(Stmt::PropertyAssignment(PropertyAssignment {
+                target: *target,
+                name,
+                op,
+                val,
+            })),
+            _ => Err(ParserError::InvalidAssignmentTarget(target_pos)),
+        }
     }
 
-    /// Attempt to parse a for loop, by parsing it into a while loop.
+    /// Attempt to parse a for loop, with the initializer in a block around the loop itself.
     fn for_stmt(&mut self) -> Result<Stmt, ParserError> {
         self.expect_consume(&[Token::Keyword(Keyword::For), Token::LeftBracket])?;
 
+        if matches!(
+            self.at(self.cursor + 1),
+            Ok(Token::Keyword(Keyword::In) | Token::Comma)
+        ) {
+            return self.for_in_stmt();
+        }
+
         let initializer = self.stmt()?;
 
         let condition = self.expr()?;
-        let increment = self.stmt()?;
+        self.expect_consume(&[Token::Semi])?;
 
-        self.expect_consume(&[Token::RightBracket])?;
+        let increment = self.simple_stmt()?;
 
-        let mut stmts = match self.block()? {
-            Stmt::Block(stmts) => stmts,
-            _ => todo!(),
-        };
+        self.expect_consume(&[Token::RightBracket])?;
 
-        stmts.push(increment);
+        let body = self.loop_body()?;
 
         Ok(Stmt::Block(vec![
             initializer,
-            Stmt::While(condition, Box::new(Stmt::Block(stmts))),
+            Stmt::For(condition, Box::new(body), Box::new(increment)),
         ]))
     }
 
+    /// Attempts to parse the rest of a for-in loop such as `for (key, value in map) { ... }`, after the `(`.
+    fn for_in_stmt(&mut self) -> Result<Stmt, ParserError> {
+        let first = self.curr()?.try_into_ident()?;
+        self.adv();
+
+        let second = match self.match_rule(&[Token::Comma]) {
+            true => {
+                let second = self.curr()?.try_into_ident()?;
+                self.adv();
+
+                Some(second)
+            }
+            false => None,
+        };
+
+        self.expect_consume(&[Token::Keyword(Keyword::In)])?;
+
+        let iterable = self.expr()?;
+
+        self.expect_consume(&[Token::RightBracket])?;
+
+        let body = self.loop_body()?;
+
+        Ok(Stmt::ForIn(ForIn {
+            first,
+            second,
+            iterable,
+            body: Box::new(body),
+        }))
+    }
+
     pub fn func(&mut self) -> Result<Stmt, ParserError> {
         self.expect_consume(&[Token::Keyword(Keyword::Func)])?;
 
@@ -216,6 +311,18 @@ impl Parser {
         let ident = self.curr()?.try_into_ident()?;
         self.adv();
 
+        let func = self.func_body()?;
+
+        // Return the function as a declaration statement
+        Ok(Stmt::Declaration(Declaration {
+            ident,
+            val: Expr::Func(func),
+        }))
+    }
+
+    /// Attempts to parse the parameters and body of a function, which come after the `func` keyword and the name of
+    /// the function if it has one.
+    pub fn func_body(&mut self) -> Result<Func, ParserError> {
         // Consume the `(`
         self.expect_consume(&[Token::LeftBracket])?;
 
@@ -224,26 +331,27 @@ impl Parser {
         // Keep parsing the arg identifiers (e.g func f(a, b, c))
         //                                              ^^^^^^^
         //                                             this part
-        loop {
-            if !self.match_rule(&[Token::Comma]) {
-                break;
-            }
-            args.push(self.curr()?.try_into_ident()?);
+        if self.curr()? != Token::RightBracket {
+            loop {
+                args.push(self.curr()?.try_into_ident()?);
 
-            self.adv();
+                self.adv();
+
+                if !self.match_rule(&[Token::Comma]) {
+                    break;
+                }
+            }
         }
 
         // Consume the `)`
         self.expect_consume(&[Token::RightBracket])?;
 
-        // Parse the body
-        let body = self.block()?;
+        // Parse the body, which isn't inside of any loop even if the function is
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.block();
+        self.loop_depth = loop_depth;
 
-        // Return the function as a declaration statement
-        Ok(Stmt::Declaration(Declaration {
-            ident,
-            val: Expr::Func(Func::new(body, args)),
-        }))
+        Ok(Func::new(body?, args))
     }
 
     pub fn return_stmt(&mut self) -> Result<Stmt, ParserError> {
@@ -254,15 +362,58 @@ impl Parser {
         Ok(Stmt::Return(expr))
     }
 
+    /// Attempts to parse a `break` or `continue` statement.
+    fn loop_control(&mut self, keyword: Keyword) -> Result<Stmt, ParserError> {
+        if self.loop_depth == 0 {
+            return Err(ParserError::OutsideLoop(keyword, self.cursor));
+        }
+
+        self.expect_consume(&[Token::Keyword(keyword.clone())])?;
+
+        match keyword {
+            Keyword::Break => Ok(Stmt::Break),
+            _ => Ok(Stmt::Continue),
+        }
+    }
+
+    /// Attempts to parse a class declaration. The body of the class is made of methods, which are written like
+    /// functions without the `func` keyword.
     pub fn class_stmt(&mut self) -> Result<Stmt, ParserError> {
         self.expect_consume(&[Token::Keyword(Keyword::Class)])?;
 
         // Get the identifier and advance.
-        let ident = self.curr()?.try_into_ident()?;
+        let name = self.curr()?.try_into_ident()?;
         self.adv();
 
+        // The class it inherits from, as in `class B < A`
+        let superclass = match self.matches(&[Token::Op(BinOp::LessSign)]) {
+            Some(_) => {
+                let superclass = self.curr()?.try_into_ident()?;
+                self.adv();
+
+                Some(Expr::Var(superclass))
+            }
+            None => None,
+        };
+
         self.expect_consume(&[Token::LeftCurly])?;
 
-        todo!()
+        let mut methods = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let method_name = self.curr()?.try_into_ident()?;
+            self.adv();
+
+            methods.push((method_name, self.func_body()?));
+        }
+
+        // Advance from the "}" token.
+        self.adv();
+
+        Ok(Stmt::Class(ClassDecl {
+            name,
+            superclass,
+            methods,
+        }))
     }
 }
@@ -1,18 +1,88 @@
 #[cfg(test)]
 /// A module for parser unit tests.
 mod parser_tests {
+    use super::expr_tests::eval;
 
     use crate::{
         expr::{BinExpr, Expr},
+        func::Func,
         lexer::{
+            err::LexerError,
             op::{BinOp, UnOp},
             token::{Keyword, Token},
             Lexer,
         },
-        parser::Parser,
-        stmt::{Declaration, Stmt},
+        parser::{err::ParserError, Parser},
+        stmt::{ClassDecl, Declaration, ForIn, IndexAssignment, PropertyAssignment, Stmt},
+        value::Value,
     };
 
+    #[test]
+    fn test_from_lexer() {
+        let s = "let a = 1; { print a + 2; } let b;";
+
+        let from_lexer = Parser::from_lexer(Lexer::new(s)).get_statements();
+        let from_tokens = Parser::new(Lexer::new(s).tokenize().unwrap()).get_statements();
+
+        assert!(from_lexer.is_ok());
+        assert_eq!(from_lexer, from_tokens);
+
+        let s = "let a = 1;\nlet b = \"unterminated;";
+
+        let result = Parser::from_lexer(Lexer::new(s)).get_statements();
+
+        assert!(matches!(
+            result,
+            Err(ParserError::LexerError(LexerError::Expected('"', _)))
+        ));
+    }
+
+    #[test]
+    fn test_drops_parsed_tokens() {
+        let s = "let a = 1; if (a > 0) { a += 1; }\n".repeat(1000);
+
+        let mut parser = Parser::from_lexer(Lexer::new(&s));
+        let stmts = parser.get_statements().unwrap();
+
+        assert_eq!(stmts.len(), 2000);
+
+        // Only the tokens of the last statement are still around
+        assert!(parser.tokens.len() <= 12, "{}", parser.tokens.len());
+        assert_eq!(parser.dropped + parser.tokens.len(), 17000);
+    }
+
+    #[test]
+    fn test_missing_semicolons() {
+        assert_eq!(
+            Parser::from_lexer(Lexer::new("print 1 2;")).get_statements(),
+            Err(ParserError::FailedRuleMatch(vec![Token::Semi], 2))
+        );
+        assert_eq!(
+            Parser::from_lexer(Lexer::new("let a = 1 let b = 2; print a + b;")).get_statements(),
+            Err(ParserError::FailedRuleMatch(vec![Token::Semi], 4))
+        );
+
+        for s in [
+            "print 1",
+            "let a = 1; a += 1",
+            "for (let i = 0; i < 3 i += 1) {}",
+            "for (let i = 0 i < 3; i += 1) {}",
+            "while (true) { break }",
+        ] {
+            assert!(
+                Parser::from_lexer(Lexer::new(s)).get_statements().is_err(),
+                "{s}"
+            );
+        }
+
+        // Statements that end with a block don't need one
+        assert!(Parser::from_lexer(Lexer::new(
+            "if (true) {} while (false) {} func f() {} class A {}"
+        ))
+        .get_statements()
+        .is_ok());
+    }
+
     #[test]
     fn test_helpers() {
         let s = "let a = (1 + 1) + 2 - 432; let b = 3;";
@@ -37,7 +107,7 @@ mod parser_tests {
             parser.adv();
         });
 
-        assert_eq!(parser.pos(), parser.tokens.len() + 1);
+        assert_eq!(parser.pos(), parser.tokens.len() - 1);
     }
 
     #[test]
@@ -101,7 +171,7 @@ mod parser_tests {
 
         let result = result.unwrap();
 
-        assert!(result.eval().is_ok());
+        assert!(eval(&result).is_ok());
     }
 
     #[test]
@@ -115,11 +185,11 @@ mod parser_tests {
 
         assert!(result.is_ok());
 
-        let result = result.unwrap().eval();
+        let result = eval(&result.unwrap());
         assert!(result.is_ok());
 
         let result = result.unwrap();
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -132,13 +202,13 @@ mod parser_tests {
         let result = parser.equality();
         assert!(result.is_ok());
 
-        let result = result.unwrap().eval();
+        let result = eval(&result.unwrap());
 
         assert!(result.is_ok());
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(true));
+        assert_eq!(result, Value::Bool(true));
     }
 
     #[test]
@@ -154,12 +224,12 @@ mod parser_tests {
         let result = parser.equality();
         assert!(result.is_ok());
 
-        let result = result.unwrap().eval();
+        let result = eval(&result.unwrap());
 
         assert!(result.is_ok());
 
         let result = result.unwrap();
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -172,13 +242,13 @@ mod parser_tests {
         let result = parser.equality();
         assert!(result.is_ok());
 
-        let result = result.unwrap().eval();
+        let result = eval(&result.unwrap());
 
         assert!(result.is_ok());
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(true));
+        assert_eq!(result, Value::Bool(true));
     }
 
     #[test]
@@ -191,13 +261,13 @@ mod parser_tests {
         let result = parser.equality();
         assert!(result.is_ok());
 
-        let result = result.unwrap().eval();
+        let result = eval(&result.unwrap());
 
         assert!(result.is_ok());
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -389,6 +459,65 @@ mod parser_tests {
         );
     }
 
+    #[test]
+    fn test_else_if_chain() {
+        let s = "
+        if (x == 1) print \"one\";
+        else if (x == 2) {
+            print \"two\";
+        } else print \"many\";
+        ";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let ast = Parser::new(tokens).stmt();
+
+        let is = |n| {
+            Expr::Bin(BinExpr {
+                lhs: Box::new(Expr::Var("x".to_owned())),
+                rhs: Box::new(Expr::Num(n)),
+                op: BinOp::EqSign,
+            })
+        };
+
+        assert_eq!(
+            ast.unwrap(),
+            Stmt::If(
+                is(1),
+                Box::new(Stmt::Print(Expr::Str("one".to_owned()))),
+                Some(Box::new(Stmt::If(
+                    is(2),
+                    Box::new(Stmt::Block(vec![Stmt::Print(Expr::Str("two".to_owned()))])),
+                    Some(Box::new(Stmt::Print(Expr::Str("many".to_owned()))))
+                )))
+            )
+        );
+    }
+
+    #[test]
+    fn test_while_loop_without_block() {
+        let s = "while (i < 3) i += 1;";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let ast = Parser::new(tokens).stmt();
+
+        assert_eq!(
+            ast.unwrap(),
+            Stmt::While(
+                Expr::Bin(BinExpr {
+                    lhs: Box::new(Expr::Var("i".to_owned())),
+                    rhs: Box::new(Expr::Num(3)),
+                    op: BinOp::LessSign
+                }),
+                Box::new(Stmt::Assignment(Declaration {
+                    ident: "i".to_owned(),
+                    val: Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Var("i".to_owned())),
+                        rhs: Box::new(Expr::Num(1)),
+                        op: BinOp::Add
+                    })
+                }))
+            )
+        );
+    }
+
     #[test]
     fn test_lonely_if_statement() {
         let s = "
@@ -504,6 +633,355 @@ mod parser_tests {
         );
     }
 
+    #[test]
+    fn test_compound_assignment() {
+        let s = "i += 1; i *= 2 + 3;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Assignment(Declaration {
+                    ident: "i".to_owned(),
+                    val: Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Var("i".to_owned())),
+                        rhs: Box::new(Expr::Num(1)),
+                        op: BinOp::Add
+                    })
+                }),
+                Stmt::Assignment(Declaration {
+                    ident: "i".to_owned(),
+                    val: Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Var("i".to_owned())),
+                        rhs: Box::new(Expr::Bin(BinExpr {
+                            lhs: Box::new(Expr::Num(2)),
+                            rhs: Box::new(Expr::Num(3)),
+                            op: BinOp::Add
+                        })),
+                        op: BinOp::Mul
+                    })
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_for_loop() {
+        let s = "for (let i = 0; i < 3; i += 1) continue;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [Stmt::Block(vec![
+                Stmt::Declaration(Declaration {
+                    ident: "i".to_owned(),
+                    val: Expr::Num(0)
+                }),
+                Stmt::For(
+                    Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Var("i".to_owned())),
+                        rhs: Box::new(Expr::Num(3)),
+                        op: BinOp::LessSign
+                    }),
+                    Box::new(Stmt::Continue),
+                    Box::new(Stmt::Assignment(Declaration {
+                        ident: "i".to_owned(),
+                        val: Expr::Bin(BinExpr {
+                            lhs: Box::new(Expr::Var("i".to_owned())),
+                            rhs: Box::new(Expr::Num(1)),
+                            op: BinOp::Add
+                        })
+                    }))
+                )
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_loop_control_outside_loop() {
+        let cases = [
+            "break;",
+            "if (true) { continue; }",
+            "while (true) { func f() { break; } }",
+        ];
+
+        for s in cases {
+            let tokens = Lexer::new(s).tokenize().unwrap();
+
+            let ast = Parser::new(tokens).get_statements();
+
+            assert!(matches!(ast, Err(ParserError::OutsideLoop(..))), "{s}");
+        }
+
+        let s = "while (true) { if (x) break; else { continue; } }";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert!(Parser::new(tokens).get_statements().is_ok());
+    }
+
+    #[test]
+    fn test_function_with_args() {
+        let s = "func add(a, b) { return a + b; } let f = func () { print 1; };";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Declaration(Declaration {
+                    ident: "add".to_owned(),
+                    val: Expr::Func(Func::new(
+                        Stmt::Block(vec![Stmt::Return(Expr::Bin(BinExpr {
+                            lhs: Box::new(Expr::Var("a".to_owned())),
+                            rhs: Box::new(Expr::Var("b".to_owned())),
+                            op: BinOp::Add
+                        }))]),
+                        vec!["a".to_owned(), "b".to_owned()]
+                    ))
+                }),
+                Stmt::Declaration(Declaration {
+                    ident: "f".to_owned(),
+                    val: Expr::Func(Func::new(
+                        Stmt::Block(vec![Stmt::Print(Expr::Num(1))]),
+                        vec![]
+                    ))
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lambda_argument() {
+        let s = "map(func (x) { return x; }, list);";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [Stmt::Expr(Expr::Funcall(
+                Box::new(Expr::Var("map".to_owned())),
+                vec![
+                    Expr::Func(Func::new(
+                        Stmt::Block(vec![Stmt::Return(Expr::Var("x".to_owned()))]),
+                        vec!["x".to_owned()]
+                    )),
+                    Expr::Var("list".to_owned())
+                ]
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        let s = "let a = [1, [2], []]; a[0] = a[1][0]; a[i] += 1;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        let var = |name: &str| Box::new(Expr::Var(name.to_owned()));
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Declaration(Declaration {
+                    ident: "a".to_owned(),
+                    val: Expr::List(vec![
+                        Expr::Num(1),
+                        Expr::List(vec![Expr::Num(2)]),
+                        Expr::List(vec![])
+                    ])
+                }),
+                Stmt::IndexAssignment(IndexAssignment {
+                    target: Expr::Var("a".to_owned()),
+                    index: Expr::Num(0),
+                    op: None,
+                    val: Expr::Index(
+                        Box::new(Expr::Index(var("a"), Box::new(Expr::Num(1)))),
+                        Box::new(Expr::Num(0))
+                    )
+                }),
+                Stmt::IndexAssignment(IndexAssignment {
+                    target: Expr::Var("a".to_owned()),
+                    index: Expr::Var("i".to_owned()),
+                    op: Some(BinOp::Add),
+                    val: Expr::Num(1)
+                })
+            ]
+        );
+
+        let tokens = Lexer::new("f() = 1;").tokenize().unwrap();
+
+        assert!(matches!(
+            Parser::new(tokens).get_statements(),
+            Err(ParserError::InvalidAssignmentTarget(0))
+        ));
+    }
+
+    #[test]
+    fn test_maps() {
+        let s = "let m = {\"a\": 1, \"b\": {}}; {\"c\": 2}; {} for (k, v in m) print k;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        let str = |s: &str| Expr::Str(s.to_owned());
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Declaration(Declaration {
+                    ident: "m".to_owned(),
+                    val: Expr::Map(vec![
+                        (str("a"), Expr::Num(1)),
+                        (str("b"), Expr::Map(vec![]))
+                    ])
+                }),
+                Stmt::Expr(Expr::Map(vec![(str("c"), Expr::Num(2))])),
+                Stmt::Block(vec![]),
+                Stmt::ForIn(ForIn {
+                    first: "k".to_owned(),
+                    second: Some("v".to_owned()),
+                    iterable: Expr::Var("m".to_owned()),
+                    body: Box::new(Stmt::Print(Expr::Var("k".to_owned())))
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_statements() {
+        let s = "{\"a\" + \"b\": 2}[\"ab\"]; {}; {}[\"x\"]; { a ? 1 : 2; } { a; } {} print 1;";
+
+        let ast = Parser::from_lexer(Lexer::new(s)).get_statements();
+
+        let str = |s: &str| Expr::Str(s.to_owned());
+        let var = |s: &str| Expr::Var(s.to_owned());
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Expr(Expr::Index(
+                    Box::new(Expr::Map(vec![(
+                        Expr::Bin(BinExpr {
+                            lhs: Box::new(str("a")),
+                            rhs: Box::new(str("b")),
+                            op: BinOp::Add
+                        }),
+                        Expr::Num(2)
+                    )])),
+                    Box::new(str("ab"))
+                )),
+                Stmt::Expr(Expr::Map(vec![])),
+                Stmt::Expr(Expr::Index(Box::new(Expr::Map(vec![])), Box::new(str("x")))),
+                Stmt::Block(vec![Stmt::Expr(Expr::Ternary(
+                    Box::new(var("a")),
+                    Box::new(Expr::Num(1)),
+                    Box::new(Expr::Num(2))
+                ))]),
+                Stmt::Block(vec![Stmt::Expr(var("a"))]),
+                Stmt::Block(vec![]),
+                Stmt::Print(Expr::Num(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deeply_nested_blocks() {
+        // Telling blocks from maps mustn't read the inner blocks over again for every block around them
+        let depth = 20;
+        let s = format!(
+            "{}print 1;{}",
+            "{ apply(func () { ".repeat(depth),
+            " }); }".repeat(depth)
+        );
+
+        let start = std::time::Instant::now();
+        let ast = Parser::from_lexer(Lexer::new(&s)).get_statements();
+
+        assert!(ast.is_ok());
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+        let ast = Parser::from_lexer(Lexer::new("{ {\"a\": 1}[\"a\"]; }")).get_statements();
+
+        assert!(matches!(ast.as_deref(), Ok([Stmt::Block(_)])));
+    }
+
+    #[test]
+    fn test_class() {
+        let s = "class Point { init(x) { this.x = x; } } p.x.y += 1;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [
+                Stmt::Class(ClassDecl {
+                    name: "Point".to_owned(),
+                    superclass: None,
+                    methods: vec![(
+                        "init".to_owned(),
+                        Func::new(
+                            Stmt::Block(vec![Stmt::PropertyAssignment(PropertyAssignment {
+                                target: Expr::This,
+                                name: "x".to_owned(),
+                                op: None,
+                                val: Expr::Var("x".to_owned())
+                            })]),
+                            vec!["x".to_owned()]
+                        )
+                    )]
+                }),
+                Stmt::PropertyAssignment(PropertyAssignment {
+                    target: Expr::Get(Box::new(Expr::Var("p".to_owned())), "x".to_owned()),
+                    name: "y".to_owned(),
+                    op: Some(BinOp::Add),
+                    val: Expr::Num(1)
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subclass() {
+        let s = "class B < A { f() { return super.f(); } }";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [Stmt::Class(ClassDecl {
+                name: "B".to_owned(),
+                superclass: Some(Expr::Var("A".to_owned())),
+                methods: vec![(
+                    "f".to_owned(),
+                    Func::new(
+                        Stmt::Block(vec![Stmt::Return(Expr::Funcall(
+                            Box::new(Expr::Super("f".to_owned())),
+                            vec![]
+                        ))]),
+                        vec![]
+                    )
+                )]
+            })]
+        );
+    }
+
     #[test]
     fn test_simple_function_call() {
         let s = "someFunction();";
@@ -596,13 +1074,28 @@ mod parser_tests {
 mod expr_tests {
     use crate::{
         expr::{BinExpr, Expr},
+        interpreter::{self, Interpreter},
         lexer::{
             op::{BinOp, UnOp},
             Lexer,
         },
         parser::Parser,
+        stmt::{Declaration, Stmt},
+        value::Value,
     };
 
+    /// Evaluates an expression by running it with the interpreter.
+    pub(super) fn eval(expr: &Expr) -> Result<Value, interpreter::Err> {
+        let mut interpreter = Interpreter::new(vec![Stmt::Declaration(Declaration {
+            ident: "result".to_owned(),
+            val: expr.clone(),
+        })])?;
+        interpreter.interpret()?;
+
+        let result = interpreter.env.borrow().get("result");
+        Ok(result.unwrap())
+    }
+
     #[test]
     fn test_primary() {
         let bool_t = "true";
@@ -683,6 +1176,47 @@ mod expr_tests {
         );
     }
 
+    #[test]
+    fn test_unary_minus() {
+        let s = "-2 * - -x";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr();
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Bin(BinExpr {
+                lhs: Box::new(Expr::Unary(UnOp::Minus, Box::new(Expr::Num(2)))),
+                rhs: Box::new(Expr::Unary(
+                    UnOp::Minus,
+                    Box::new(Expr::Unary(
+                        UnOp::Minus,
+                        Box::new(Expr::Var("x".to_owned()))
+                    ))
+                )),
+                op: BinOp::Mul
+            })
+        );
+
+        let s = "!!true";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr().unwrap();
+
+        assert_eq!(
+            expr,
+            Expr::Unary(
+                UnOp::Bang,
+                Box::new(Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true))))
+            )
+        );
+        assert_eq!(eval(&expr).unwrap(), Value::Bool(true));
+
+        let s = "3 - -2";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr().unwrap();
+
+        assert_eq!(eval(&expr).unwrap(), Value::Num(5));
+    }
+
     #[test]
     fn test_factor() {
         let s = "!true * false";
@@ -919,4 +1453,205 @@ mod expr_tests {
             })
         );
     }
+
+    #[test]
+    fn test_float_arithmetic() {
+        let cases = [
+            ("1.5 + 2", Value::Float(3.5)),
+            ("7 / 2", Value::Num(3)),
+            ("7.0 / 2", Value::Float(3.5)),
+            ("2 * .25 - 1", Value::Float(-0.5)),
+            ("1 == 1.0", Value::Bool(true)),
+            ("2.5 > 2", Value::Bool(true)),
+        ];
+
+        for (s, expected) in cases {
+            let tokens = Lexer::new(s).tokenize().unwrap();
+            let expr = Parser::new(tokens).expr().unwrap();
+
+            assert_eq!(eval(&expr).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_power_precedence() {
+        let s = "-2 ** 3 ** 2 % 5";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr();
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Bin(BinExpr {
+                lhs: Box::new(Expr::Unary(
+                    UnOp::Minus,
+                    Box::new(Expr::Bin(BinExpr {
+                        lhs: Box::new(Expr::Num(2)),
+                        rhs: Box::new(Expr::Bin(BinExpr {
+                            lhs: Box::new(Expr::Num(3)),
+                            rhs: Box::new(Expr::Num(2)),
+                            op: BinOp::Pow
+                        })),
+                        op: BinOp::Pow
+                    }))
+                )),
+                rhs: Box::new(Expr::Num(5)),
+                op: BinOp::Mod
+            })
+        );
+    }
+
+    #[test]
+    fn test_modulo_and_floor_division() {
+        let cases = [
+            ("7 % 3", Value::Num(1)),
+            ("-7 % 3", Value::Num(2)),
+            ("7 % -3", Value::Num(-2)),
+            ("5.5 % 2", Value::Float(1.5)),
+            ("-5.5 % 2", Value::Float(0.5)),
+            ("7 ~/ 2", Value::Num(3)),
+            ("-7 ~/ 2", Value::Num(-4)),
+            ("-6 ~/ 2", Value::Num(-3)),
+            ("7.5 ~/ 2", Value::Float(3.0)),
+            ("2 ** 3 ** 2", Value::Num(512)),
+            ("-2 ** 2", Value::Num(-4)),
+            ("(-2) ** 2", Value::Num(4)),
+            ("2 ** -1", Value::Float(0.5)),
+            ("4 ** 0.5", Value::Float(2.0)),
+        ];
+
+        for (s, expected) in cases {
+            let tokens = Lexer::new(s).tokenize().unwrap();
+            let expr = Parser::new(tokens).expr().unwrap();
+
+            assert_eq!(eval(&expr).unwrap(), expected, "{s}");
+        }
+    }
+
+    #[test]
+    fn test_bitwise_precedence() {
+        let s = "a | b ^ c & d == 1 << 2 + 1";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr();
+
+        let var = |name: &str| Box::new(Expr::Var(name.to_owned()));
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Bin(BinExpr {
+                lhs: var("a"),
+                rhs: Box::new(Expr::Bin(BinExpr {
+                    lhs: var("b"),
+                    rhs: Box::new(Expr::Bin(BinExpr {
+                        lhs: var("c"),
+                        rhs: Box::new(Expr::Bin(BinExpr {
+                            lhs: var("d"),
+                            rhs: Box::new(Expr::Bin(BinExpr {
+                                lhs: Box::new(Expr::Num(1)),
+                                rhs: Box::new(Expr::Bin(BinExpr {
+                                    lhs: Box::new(Expr::Num(2)),
+                                    rhs: Box::new(Expr::Num(1)),
+                                    op: BinOp::Add
+                                })),
+                                op: BinOp::Shl
+                            })),
+                            op: BinOp::EqSign
+                        })),
+                        op: BinOp::BitAnd
+                    })),
+                    op: BinOp::BitXor
+                })),
+                op: BinOp::BitOr
+            })
+        );
+    }
+
+    #[test]
+    fn test_bitwise_eval() {
+        let cases = [
+            ("12 & 10", Value::Num(8)),
+            ("12 | 10", Value::Num(14)),
+            ("12 ^ 10", Value::Num(6)),
+            ("~5", Value::Num(-6)),
+            ("1 << 62", Value::Num(1 << 62)),
+            (
+                "1 << 63",
+                Value::BigNum("9223372036854775808".parse().unwrap()),
+            ),
+            (
+                "1 << 62 << 1",
+                Value::BigNum("9223372036854775808".parse().unwrap()),
+            ),
+            ("-1 << 63", Value::Num(i64::MIN)),
+            (
+                "1 << 64",
+                Value::BigNum("18446744073709551616".parse().unwrap()),
+            ),
+            ("-16 >> 2", Value::Num(-4)),
+            ("-16 >> 100", Value::Num(-1)),
+            ("(1 << 100) >> 98", Value::Num(4)),
+            ("100000000000000000000 & 1", Value::Num(0)),
+            (
+                "100000000000000000000 | 1",
+                Value::BigNum("100000000000000000001".parse().unwrap()),
+            ),
+            (
+                "(1 << 64) ^ -1",
+                Value::BigNum("-18446744073709551617".parse().unwrap()),
+            ),
+            (
+                "~100000000000000000000",
+                Value::BigNum("-100000000000000000001".parse().unwrap()),
+            ),
+        ];
+
+        for (s, expected) in cases {
+            let tokens = Lexer::new(s).tokenize().unwrap();
+            let expr = Parser::new(tokens).expr().unwrap();
+
+            assert_eq!(eval(&expr).unwrap(), expected, "{s}");
+        }
+
+        for s in [
+            "1 >> -1",
+            "(1 << 64) << -1",
+            "1.5 & 1",
+            "1 << 1000000",
+            "~1.5",
+        ] {
+            let tokens = Lexer::new(s).tokenize().unwrap();
+            let expr = Parser::new(tokens).expr().unwrap();
+
+            assert!(eval(&expr).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_ternary() {
+        let s = "a or b ? 1 : c ? 2 : 3";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr();
+
+        let var = |name: &str| Box::new(Expr::Var(name.to_owned()));
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Ternary(
+                Box::new(Expr::Bin(BinExpr {
+                    lhs: var("a"),
+                    rhs: var("b"),
+                    op: BinOp::Or
+                })),
+                Box::new(Expr::Num(1)),
+                Box::new(Expr::Ternary(
+                    var("c"),
+                    Box::new(Expr::Num(2)),
+                    Box::new(Expr::Num(3))
+                ))
+            )
+        );
+
+        let tokens = Lexer::new("a ? 1").tokenize().unwrap();
+
+        assert!(Parser::new(tokens).expr().is_err());
+    }
 }
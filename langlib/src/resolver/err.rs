@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+/// Error enum for the `Resolver` struct.
+pub enum ResolverError {
+    #[error("Can't read local variable \"{0}\" in its own initializer.")]
+    ReadInOwnInitializer(String),
+
+    #[error("Variable \"{0}\" is already declared in this scope.")]
+    Redeclared(String),
+
+    #[error("Can't return from top-level code.")]
+    TopLevelReturn,
+
+    #[error("Can't use \"this\" outside of a class.")]
+    ThisOutsideClass,
+
+    #[error("Can't use \"super\" outside of a class with a superclass.")]
+    SuperOutsideSubclass,
+}
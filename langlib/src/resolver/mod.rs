@@ -0,0 +1,335 @@
+pub mod err;
+mod tests;
+
+use std::rc::Rc;
+
+use ahash::AHashMap;
+
+use crate::{
+    expr::{BinExpr, Expr},
+    func::Func,
+    stmt::{ClassDecl, Declaration, ForIn, IndexAssignment, PropertyAssignment, Stmt},
+};
+
+use self::err::ResolverError;
+
+/// The kind of class that the resolver is currently inside of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassKind {
+    None,
+    Class,
+    Subclass,
+}
+
+/// A pass between parsing and interpreting, which works out which scope every variable refers to. Variables in local
+/// scopes are rewritten into `Expr::Local` and `Stmt::LocalAssignment` with the number of scopes between the use and
+/// the declaration, and everything else is left to be a global.
+pub struct Resolver {
+    /// The local scopes, mapping each variable to whether its initializer has been resolved yet.
+    scopes: Vec<AHashMap<String, bool>>,
+    /// How many functions the code being resolved is nested in.
+    function_depth: usize,
+    class: ClassKind,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            scopes: Vec::new(),
+            function_depth: 0,
+            class: ClassKind::None,
+        }
+    }
+
+    /// Resolves a list of statements, returning them with their variables resolved.
+    pub fn resolve(&mut self, stmts: Vec<Stmt>) -> Result<Vec<Stmt>, ResolverError> {
+        stmts.into_iter().map(|stmt| self.stmt(stmt)).collect()
+    }
+
+    fn stmt(&mut self, stmt: Stmt) -> Result<Stmt, ResolverError> {
+        Ok(match stmt {
+            Stmt::Declaration(Declaration { ident, val }) => {
+                // A function may call itself, so it's defined before its body is resolved
+                let val = if let Expr::Func(_) = val {
+                    self.declare(&ident)?;
+                    self.define(&ident);
+
+                    self.expr(val)?
+                } else {
+                    self.declare(&ident)?;
+                    let val = self.expr(val)?;
+                    self.define(&ident);
+
+                    val
+                };
+
+                Stmt::Declaration(Declaration { ident, val })
+            }
+            Stmt::Assignment(Declaration { ident, val }) => {
+                let val = self.expr(val)?;
+
+                match self.depth_of(&ident) {
+                    Some(depth) => Stmt::LocalAssignment(Declaration { ident, val }, depth),
+                    None => Stmt::Assignment(Declaration { ident, val }),
+                }
+            }
+            Stmt::LocalAssignment(declaration, depth) => Stmt::LocalAssignment(declaration, depth),
+            Stmt::IndexAssignment(assignment) => Stmt::IndexAssignment(IndexAssignment {
+                target: self.expr(assignment.target)?,
+                index: self.expr(assignment.index)?,
+                op: assignment.op,
+                val: self.expr(assignment.val)?,
+            }),
+            Stmt::PropertyAssignment(assignment) => Stmt::PropertyAssignment(PropertyAssignment {
+                target: self.expr(assignment.target)?,
+                name: assignment.name,
+                op: assignment.op,
+                val: self.expr(assignment.val)?,
+            }),
+            Stmt::Print(expr) => Stmt::Print(self.expr(expr)?),
+            Stmt::Expr(expr) => Stmt::Expr(self.expr(expr)?),
+            Stmt::Block(stmts) => {
+                self.begin_scope();
+                let stmts = self.resolve(stmts);
+                self.end_scope();
+
+                Stmt::Block(stmts?)
+            }
+            Stmt::If(condition, body, else_body) => Stmt::If(
+                self.expr(condition)?,
+                Box::new(self.stmt(*body)?),
+                match else_body {
+                    Some(else_body) => Some(Box::new(self.stmt(*else_body)?)),
+                    None => None,
+                },
+            ),
+            Stmt::While(condition, body) => {
+                Stmt::While(self.expr(condition)?, Box::new(self.stmt(*body)?))
+            }
+            Stmt::For(condition, body, increment) => Stmt::For(
+                self.expr(condition)?,
+                Box::new(self.stmt(*body)?),
+                Box::new(self.stmt(*increment)?),
+            ),
+            Stmt::ForIn(for_in) => {
+                let iterable = self.expr(for_in.iterable)?;
+
+                // The loop variables live in a scope of their own around the body
+                self.begin_scope();
+
+                let body = self.for_in_body(&for_in.first, &for_in.second, *for_in.body);
+
+                self.end_scope();
+
+                Stmt::ForIn(ForIn {
+                    first: for_in.first,
+                    second: for_in.second,
+                    iterable,
+                    body: Box::new(body?),
+                })
+            }
+            Stmt::Class(decl) => Stmt::Class(self.class(decl)?),
+            Stmt::Return(expr) => {
+                if self.function_depth == 0 {
+                    return Err(ResolverError::TopLevelReturn);
+                }
+
+                Stmt::Return(self.expr(expr)?)
+            }
+            Stmt::Break => Stmt::Break,
+            Stmt::Continue => Stmt::Continue,
+        })
+    }
+
+    /// Resolves the body of a for-in loop, after declaring its variables.
+    fn for_in_body(
+        &mut self,
+        first: &str,
+        second: &Option<String>,
+        body: Stmt,
+    ) -> Result<Stmt, ResolverError> {
+        self.declare(first)?;
+        self.define(first);
+
+        if let Some(second) = second {
+            self.declare(second)?;
+            self.define(second);
+        }
+
+        self.stmt(body)
+    }
+
+    /// Resolves a class declaration. Methods are resolved inside of a scope that holds `this`, which in turn is inside
+    /// of a scope that holds `super` if the class has a superclass.
+    fn class(&mut self, decl: ClassDecl) -> Result<ClassDecl, ResolverError> {
+        self.declare(&decl.name)?;
+        self.define(&decl.name);
+
+        let superclass = match decl.superclass {
+            Some(superclass) => Some(self.expr(superclass)?),
+            None => None,
+        };
+
+        let enclosing = self.class;
+        self.class = match superclass {
+            Some(_) => ClassKind::Subclass,
+            None => ClassKind::Class,
+        };
+
+        if superclass.is_some() {
+            self.begin_scope();
+            self.define("super");
+        }
+
+        self.begin_scope();
+        self.define("this");
+
+        let methods: Result<Vec<(String, Func)>, ResolverError> = decl
+            .methods
+            .into_iter()
+            .map(|(name, method)| Ok((name, self.func(method)?)))
+            .collect();
+
+        self.end_scope();
+
+        if superclass.is_some() {
+            self.end_scope();
+        }
+
+        self.class = enclosing;
+
+        Ok(ClassDecl {
+            name: decl.name,
+            superclass,
+            methods: methods?,
+        })
+    }
+
+    /// Resolves a function, whose arguments get a scope of their own around the body.
+    fn func(&mut self, func: Func) -> Result<Func, ResolverError> {
+        self.function_depth += 1;
+        self.begin_scope();
+
+        let body = self.func_body(&func.args, Rc::unwrap_or_clone(func.instructions));
+
+        self.end_scope();
+        self.function_depth -= 1;
+
+        Ok(Func {
+            instructions: Rc::new(body?),
+            ..func
+        })
+    }
+
+    /// Resolves the body of a function, after declaring its arguments.
+    fn func_body(&mut self, args: &[String], body: Stmt) -> Result<Stmt, ResolverError> {
+        for arg in args {
+            self.declare(arg)?;
+            self.define(arg);
+        }
+
+        self.stmt(body)
+    }
+
+    fn expr(&mut self, expr: Expr) -> Result<Expr, ResolverError> {
+        Ok(match expr {
+            Expr::Var(name) => {
+                if self.scopes.last().and_then(|scope| scope.get(&name)) == Some(&false) {
+                    return Err(ResolverError::ReadInOwnInitializer(name));
+                }
+
+                match self.depth_of(&name) {
+                    Some(depth) => Expr::Local(name, depth),
+                    None => Expr::Var(name),
+                }
+            }
+            Expr::This => match (self.class, self.depth_of("this")) {
+                (ClassKind::None, _) | (_, None) => return Err(ResolverError::ThisOutsideClass),
+                (_, Some(depth)) => Expr::Local(String::from("this"), depth),
+            },
+            Expr::Super(method) => match (self.class, self.depth_of("super")) {
+                (ClassKind::Subclass, Some(depth)) => Expr::LocalSuper(method, depth),
+                _ => return Err(ResolverError::SuperOutsideSubclass),
+            },
+            Expr::Bin(bin_expr) => Expr::Bin(BinExpr {
+                lhs: Box::new(self.expr(*bin_expr.lhs)?),
+                rhs: Box::new(self.expr(*bin_expr.rhs)?),
+                op: bin_expr.op,
+            }),
+            Expr::Unary(op, expr) => Expr::Unary(op, Box::new(self.expr(*expr)?)),
+            Expr::Ternary(condition, then, otherwise) => Expr::Ternary(
+                Box::new(self.expr(*condition)?),
+                Box::new(self.expr(*then)?),
+                Box::new(self.expr(*otherwise)?),
+            ),
+            Expr::Funcall(callee, args) => Expr::Funcall(
+                Box::new(self.expr(*callee)?),
+                args.into_iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Func(func) => Expr::Func(self.func(func)?),
+            Expr::List(items) => Expr::List(
+                items
+                    .into_iter()
+                    .map(|item| self.expr(item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Map(entries) => Expr::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, val)| Ok((self.expr(key)?, self.expr(val)?)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Expr::Index(target, index) => {
+                Expr::Index(Box::new(self.expr(*target)?), Box::new(self.expr(*index)?))
+            }
+            Expr::Get(target, name) => Expr::Get(Box::new(self.expr(*target)?), name),
+
+            // Everything else is a literal, a value, or has been resolved already
+            expr => expr,
+        })
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(AHashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Adds a variable to the innermost scope, without marking it as ready to be read yet.
+    fn declare(&mut self, name: &str) -> Result<(), ResolverError> {
+        if let Some(scope) = self.scopes.last_mut() {
+            if scope.contains_key(name) {
+                return Err(ResolverError::Redeclared(name.to_owned()));
+            }
+
+            scope.insert(name.to_owned(), false);
+        }
+
+        Ok(())
+    }
+
+    /// Marks a variable in the innermost scope as ready to be read.
+    fn define(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), true);
+        }
+    }
+
+    /// Returns how many scopes up the variable was declared, or `None` if it's a global.
+    fn depth_of(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .position(|scope| scope.contains_key(name))
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+/// A module for resolver unit tests.
+mod resolver_tests {
+    use crate::{
+        expr::Expr,
+        lexer::Lexer,
+        parser::Parser,
+        resolver::{err::ResolverError, Resolver},
+        stmt::Stmt,
+    };
+
+    /// Parses and resolves the source code.
+    fn resolve(source: &str) -> Result<Vec<Stmt>, ResolverError> {
+        let stmts = Parser::from_lexer(Lexer::new(source))
+            .get_statements()
+            .unwrap();
+
+        Resolver::new().resolve(stmts)
+    }
+
+    #[test]
+    fn test_depths() {
+        let stmts = resolve("let a = 1; { let b = 2; { print b; print a; b = 3; } }").unwrap();
+
+        let Stmt::Block(outer) = &stmts[1] else {
+            panic!("expected a block, got {:?}", stmts[1]);
+        };
+        let Stmt::Block(inner) = &outer[1] else {
+            panic!("expected a block, got {:?}", outer[1]);
+        };
+
+        assert_eq!(inner[0], Stmt::Print(Expr::Local(String::from("b"), 1)));
+        assert_eq!(inner[1], Stmt::Print(Expr::Var(String::from("a"))));
+        assert!(matches!(&inner[2], Stmt::LocalAssignment(decl, 1) if decl.ident == "b"));
+    }
+
+    #[test]
+    fn test_globals_stay_dynamic() {
+        let stmts = resolve("func f() { return g(); } func g() { return 1; }").unwrap();
+
+        assert_eq!(stmts.len(), 2);
+        assert!(resolve("let a = 1; let a = 2;").is_ok());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            resolve("{ let a = a; }"),
+            Err(ResolverError::ReadInOwnInitializer(String::from("a")))
+        );
+        assert_eq!(
+            resolve("{ let a = 1; let a = 2; }"),
+            Err(ResolverError::Redeclared(String::from("a")))
+        );
+        assert_eq!(resolve("return 1;"), Err(ResolverError::TopLevelReturn));
+        assert_eq!(
+            resolve("let a = this;"),
+            Err(ResolverError::ThisOutsideClass)
+        );
+        assert_eq!(
+            resolve("class A { f() { return super.f(); } }"),
+            Err(ResolverError::SuperOutsideSubclass)
+        );
+    }
+}
@@ -1,16 +1,27 @@
+use super::{expr::Expr, func::Func, lexer::op::BinOp};
 
-use super::expr::Expr;
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     Declaration(Declaration),
     Assignment(Declaration),
+    /// An assignment to a variable that the resolver found in a local scope, the given number of scopes up.
+    LocalAssignment(Declaration, usize),
+    IndexAssignment(IndexAssignment),
+    PropertyAssignment(PropertyAssignment),
     Print(Expr),
     Expr(Expr),
     Block(Vec<Stmt>),
+    /// An if statement with an optional else branch. Like the conditions of loops, the condition only has to be truthy.
     If(Expr, Box<Stmt>, Option<Box<Stmt>>),
     While(Expr, Box<Stmt>),
+    /// The loop of a for statement, made of its condition, body and increment. The initializer is declared in a block
+    /// around it.
+    For(Expr, Box<Stmt>, Box<Stmt>),
+    ForIn(ForIn),
+    Class(ClassDecl),
     Return(Expr),
+    Break,
+    Continue,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
@@ -22,12 +33,51 @@ pub enum StmtErr {
     UnknownKeyword,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Declaration {
     pub ident: String,
     pub val: Expr,
 }
 
+/// An assignment to an element of a list, such as `a[i] = v`. Compound assignments like `a[i] += v` keep their
+/// operator, so that `a` and `i` are only evaluated once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexAssignment {
+    pub target: Expr,
+    pub index: Expr,
+    pub op: Option<BinOp>,
+    pub val: Expr,
+}
+
+/// An assignment to a property of an instance, such as `a.b = v`. Like with `IndexAssignment`, compound assignments
+/// keep their operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyAssignment {
+    pub target: Expr,
+    pub name: String,
+    pub op: Option<BinOp>,
+    pub val: Expr,
+}
+
+/// A class declaration, made of its name, the class it inherits from and its methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDecl {
+    pub name: String,
+    pub superclass: Option<Expr>,
+    pub methods: Vec<(String, Func)>,
+}
+
+/// A loop over the items of a list or the entries of a map, such as `for (key, value in map) { ... }`. The first
+/// variable is the index or key, and the optional second one is the item or value. With only one variable, lists give
+/// their items and maps give their keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForIn {
+    pub first: String,
+    pub second: Option<String>,
+    pub iterable: Expr,
+    pub body: Box<Stmt>,
+}
+
 #[cfg(test)]
 mod stmt_tests {
     use crate::{
@@ -0,0 +1,488 @@
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    ops::{Add, Rem},
+    rc::Rc,
+};
+
+use ahash::AHashMap;
+use colored::Colorize;
+
+use crate::{
+    bigint::BigInt,
+    class::{Class, InstanceRef},
+    expr::ExprError,
+    func::Closure,
+    lexer::op::{BinOp, UnOp},
+    parser::err::ParserError,
+};
+
+/// A list that can be shared and mutated by everything that refers to it.
+pub type List = Rc<RefCell<Vec<Value>>>;
+
+/// A map from strings to values that can be shared and mutated by everything that refers to it.
+pub type Map = Rc<RefCell<AHashMap<String, Value>>>;
+
+/// The number of bits that the result of an int operation is allowed to have, so that a script can't make the host run
+/// out of memory or spend forever multiplying.
+const MAX_BIGNUM_BITS: u64 = 1 << 18;
+
+/// A value that exists while the code runs. Functions, lists, maps and instances are shared, so copying a value never
+/// copies what's behind them.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Num(i64),
+    /// An int that doesn't fit in an `i64`. Ints are only ever stored as bignums when they have to be.
+    BigNum(BigInt),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Func(Rc<Closure>),
+    List(List),
+    Map(Map),
+    Class(Rc<Class>),
+    Instance(InstanceRef),
+    Null,
+}
+
+impl Value {
+    /// Returns a boolean indicating whether this left operand alone decides the result of the logical operator `op`,
+    /// which is when it's falsy for `and` or truthy for `or`.
+    pub fn decides(&self, op: &BinOp) -> Result<bool, ParserError> {
+        let truthy: bool = self.clone().try_into()?;
+
+        Ok(match op {
+            BinOp::Or => truthy,
+            _ => !truthy,
+        })
+    }
+
+    /// Applies a unary operator to the value.
+    pub fn unary(self, op: &UnOp) -> Result<Value, ParserError> {
+        match (op, self) {
+            (UnOp::Bang, Value::Bool(bool)) => Ok(Value::Bool(!bool)),
+            (UnOp::Minus, Value::Num(num)) => match num.checked_neg() {
+                Some(num) => Ok(Value::Num(num)),
+                None => Ok(Value::from(-&BigInt::from(num))),
+            },
+            (UnOp::Minus, Value::BigNum(num)) => Ok(Value::from(-&num)),
+            (UnOp::Minus, Value::Float(num)) => Ok(Value::Float(-num)),
+            (UnOp::BitNot, Value::Num(num)) => Ok(Value::Num(!num)),
+            (UnOp::BitNot, Value::BigNum(num)) => Ok(Value::from(!&num)),
+            _ => Err(ParserError::ExprError(ExprError::InvalidUnaryOperation)),
+        }
+    }
+
+    /// Applies a binary operator to both of the operands. Logical operators don't short-circuit here, since both of
+    /// the operands have already been evaluated.
+    pub fn binary(op: &BinOp, lhs: Value, rhs: Value) -> Result<Value, ParserError> {
+        match op {
+            BinOp::Add => match Operands::new(&lhs, &rhs) {
+                // Addition
+                Ok(Operands::Ints(a, b)) => int_op(op, a, b),
+                Ok(Operands::BigInts(a, b)) => bigint_op(op, a, b),
+                Ok(Operands::Floats(a, b)) => Ok(Value::Float(a + b)),
+
+                // String concatenation
+                Err(_) => Ok(Value::Str(format!("{lhs}{rhs}"))),
+            },
+            BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::FloorDiv | BinOp::Pow => {
+                match Operands::new(&lhs, &rhs)? {
+                    // A negative exponent gives a fraction, so it can't stay an int
+                    Operands::Ints(lhs, rhs) if *op == BinOp::Pow && rhs < 0 => {
+                        Ok(Value::Float((lhs as f64).powf(rhs as f64)))
+                    }
+                    Operands::BigInts(lhs, rhs) if *op == BinOp::Pow && rhs.is_negative() => {
+                        Ok(Value::Float(lhs.to_f64().powf(rhs.to_f64())))
+                    }
+                    Operands::Ints(lhs, rhs) => int_op(op, lhs, rhs),
+                    Operands::BigInts(lhs, rhs) => bigint_op(op, lhs, rhs),
+                    Operands::Floats(lhs, rhs) => Ok(Value::Float(match op {
+                        BinOp::Sub => lhs - rhs,
+                        BinOp::Mul => lhs * rhs,
+                        BinOp::Div => lhs / rhs,
+                        BinOp::Mod => floor_mod(lhs, rhs),
+                        BinOp::FloorDiv => (lhs / rhs).floor(),
+                        _ => lhs.powf(rhs),
+                    })),
+                }
+            }
+            BinOp::EqSign => Ok(Value::Bool(lhs.equals(&rhs))),
+            BinOp::NeqSign => Ok(Value::Bool(!lhs.equals(&rhs))),
+            BinOp::GreaterSign => Ok(Value::Bool(match Operands::new(&lhs, &rhs)? {
+                Operands::Ints(lhs, rhs) => lhs > rhs,
+                Operands::BigInts(lhs, rhs) => lhs > rhs,
+                Operands::Floats(lhs, rhs) => lhs > rhs,
+            })),
+            BinOp::LessSign => Ok(Value::Bool(match Operands::new(&lhs, &rhs)? {
+                Operands::Ints(lhs, rhs) => lhs < rhs,
+                Operands::BigInts(lhs, rhs) => lhs < rhs,
+                Operands::Floats(lhs, rhs) => lhs < rhs,
+            })),
+            BinOp::GreaterEqSign => Ok(Value::Bool(match Operands::new(&lhs, &rhs)? {
+                Operands::Ints(lhs, rhs) => lhs >= rhs,
+                Operands::BigInts(lhs, rhs) => lhs >= rhs,
+                Operands::Floats(lhs, rhs) => lhs >= rhs,
+            })),
+            BinOp::LessEqSign => Ok(Value::Bool(match Operands::new(&lhs, &rhs)? {
+                Operands::Ints(lhs, rhs) => lhs <= rhs,
+                Operands::BigInts(lhs, rhs) => lhs <= rhs,
+                Operands::Floats(lhs, rhs) => lhs <= rhs,
+            })),
+            BinOp::And | BinOp::Or => match lhs.decides(op)? {
+                true => Ok(lhs),
+                false => Ok(rhs),
+            },
+            BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor | BinOp::Shl | BinOp::Shr => {
+                match Operands::new(&lhs, &rhs)? {
+                    Operands::Ints(lhs, rhs) => int_op(op, lhs, rhs),
+                    Operands::BigInts(lhs, rhs) => bigint_op(op, lhs, rhs),
+                    // Floats don't have bits to work with
+                    Operands::Floats(..) => {
+                        Err(ParserError::ExprError(ExprError::FailedConversion))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks if two values are equal. Numbers are compared by value, so `1 == 1.0`, while lists, maps, classes and
+    /// instances are only equal to themselves.
+    fn equals(&self, other: &Value) -> bool {
+        match Operands::new(self, other) {
+            Ok(Operands::Ints(lhs, rhs)) => lhs == rhs,
+            Ok(Operands::BigInts(lhs, rhs)) => lhs == rhs,
+            Ok(Operands::Floats(lhs, rhs)) => lhs == rhs,
+            Err(_) => self == other,
+        }
+    }
+
+    /// Writes the value, where `printing` holds the lists and maps that it's nested in. A list or map that's reached
+    /// again inside of itself is written as `[...]` or `{...}`, so that printing it doesn't go on forever.
+    fn write(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        printing: &mut Vec<*const ()>,
+    ) -> std::fmt::Result {
+        match self {
+            Value::Num(num) => write!(f, "{}", format!("{num}").yellow()),
+            Value::BigNum(num) => write!(f, "{}", format!("{num}").yellow()),
+
+            // Use the debug representation so that whole floats still print with a `.0`
+            Value::Float(num) => write!(f, "{}", format!("{num:?}").yellow()),
+
+            Value::Str(string) => write!(f, "{string}"),
+
+            Value::Bool(bool) => write!(f, "{}", format!("{bool}").yellow()),
+            Value::Null => write!(f, "{}", "null".bright_black()),
+            Value::Func(_) => write!(f, "{}", "<func>".bright_black()),
+            Value::Class(class) => {
+                write!(f, "{}", format!("<class {}>", class.name).bright_black())
+            }
+            Value::Instance(instance) => write!(
+                f,
+                "{}",
+                format!("<{} instance>", instance.borrow().class.name).bright_black()
+            ),
+            Value::List(list) if printing.contains(&Rc::as_ptr(list).cast()) => write!(f, "[...]"),
+            Value::List(list) => {
+                printing.push(Rc::as_ptr(list).cast());
+                write!(f, "[")?;
+
+                for (i, item) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write(f, printing)?;
+                }
+
+                printing.pop();
+                write!(f, "]")
+            }
+            Value::Map(map) if printing.contains(&Rc::as_ptr(map).cast()) => write!(f, "{{...}}"),
+            Value::Map(map) => {
+                printing.push(Rc::as_ptr(map).cast());
+                let map = map.borrow();
+
+                // Sort the keys so that maps always print the same way
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+
+                write!(f, "{{")?;
+
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key:?}: ")?;
+                    map[key].write(f, printing)?;
+                }
+
+                printing.pop();
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl PartialEq for Value {
+    /// Shared values are compared by identity, since two of them with the same contents are still different objects
+    /// and they can contain themselves. Only scalars are compared by what's in them.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Num(lhs), Value::Num(rhs)) => lhs == rhs,
+            (Value::BigNum(lhs), Value::BigNum(rhs)) => lhs == rhs,
+            (Value::Float(lhs), Value::Float(rhs)) => lhs == rhs,
+            (Value::Str(lhs), Value::Str(rhs)) => lhs == rhs,
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+            (Value::Func(lhs), Value::Func(rhs)) => lhs == rhs,
+            (Value::List(lhs), Value::List(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Map(lhs), Value::Map(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Class(lhs), Value::Class(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Instance(lhs), Value::Instance(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Null, Value::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, &mut Vec::new())
+    }
+}
+
+impl From<BigInt> for Value {
+    /// Bignums that fit in an `i64` go back to being regular ints.
+    fn from(num: BigInt) -> Self {
+        match num.to_i64() {
+            Some(num) => Value::Num(num),
+            None => Value::BigNum(num),
+        }
+    }
+}
+
+impl TryInto<i64> for Value {
+    type Error = ParserError;
+
+    fn try_into(self) -> Result<i64, Self::Error> {
+        match self {
+            Value::Num(num) => Ok(num),
+            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
+        }
+    }
+}
+
+impl TryInto<f64> for Value {
+    type Error = ParserError;
+
+    fn try_into(self) -> Result<f64, Self::Error> {
+        match self {
+            Value::Num(num) => Ok(num as f64),
+            Value::BigNum(num) => Ok(num.to_f64()),
+            Value::Float(num) => Ok(num),
+            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
+        }
+    }
+}
+
+/// The truthiness of a value, which is what the conditions of `if`, `while` and `for` statements are checked against.
+/// Positive numbers and non-empty strings are truthy, while `null` is falsy. Lists, maps, functions, classes and
+/// instances can't be used as a condition.
+impl TryInto<bool> for Value {
+    type Error = ParserError;
+
+    fn try_into(self) -> Result<bool, Self::Error> {
+        match self {
+            Value::Bool(bool) => Ok(bool),
+            Value::Num(num) => Ok(num > 0),
+            Value::BigNum(num) => Ok(!num.is_negative()),
+            Value::Float(num) => Ok(num > 0.0),
+            Value::Str(s) => Ok(!s.is_empty()),
+            Value::Null => Ok(false),
+            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
+        }
+    }
+}
+
+impl TryInto<String> for Value {
+    type Error = ParserError;
+
+    fn try_into(self) -> Result<String, Self::Error> {
+        match self {
+            Value::Str(s) => Ok(s),
+            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
+        }
+    }
+}
+
+/// Applies an arithmetic or bitwise operator to two ints. When the result doesn't fit in an `i64` it's worked out with bignums
+/// instead.
+fn int_op(op: &BinOp, lhs: i64, rhs: i64) -> Result<Value, ParserError> {
+    if rhs == 0 && matches!(op, BinOp::Div | BinOp::Mod | BinOp::FloorDiv) {
+        return Err(ParserError::ExprError(ExprError::DivisionByZero(
+            op.clone(),
+            BigInt::from(lhs),
+        )));
+    }
+
+    let result = match op {
+        BinOp::Add => lhs.checked_add(rhs),
+        BinOp::Sub => lhs.checked_sub(rhs),
+        BinOp::Mul => lhs.checked_mul(rhs),
+        // Dividing two ints is still integer division
+        BinOp::Div => lhs.checked_div(rhs),
+        // Checking the remainder first catches `i64::MIN % -1`, which overflows
+        BinOp::Mod => lhs.checked_rem(rhs).map(|_| floor_mod(lhs, rhs)),
+        BinOp::FloorDiv => lhs.checked_div(rhs).map(|quotient| {
+            // Integer division rounds towards zero, so it needs to go one lower when the result is negative
+            if lhs % rhs != 0 && (lhs < 0) != (rhs < 0) {
+                quotient - 1
+            } else {
+                quotient
+            }
+        }),
+        BinOp::Pow => u32::try_from(rhs).ok().and_then(|exp| lhs.checked_pow(exp)),
+        BinOp::BitAnd => Some(lhs & rhs),
+        BinOp::BitOr => Some(lhs | rhs),
+        BinOp::BitXor => Some(lhs ^ rhs),
+        // The shift overflows when shifting back doesn't give the same int
+        BinOp::Shl => u32::try_from(rhs)
+            .ok()
+            .and_then(|shift| lhs.checked_shl(shift).filter(|num| num >> shift == lhs)),
+        // Shifting by the width of the int or more leaves only the sign
+        BinOp::Shr => u32::try_from(rhs)
+            .ok()
+            .map(|shift| lhs >> shift.min(i64::BITS - 1)),
+        _ => unreachable!("{op} is not an arithmetic or bitwise operator"),
+    };
+
+    match result {
+        Some(num) => Ok(Value::Num(num)),
+        None => bigint_op(op, BigInt::from(lhs), BigInt::from(rhs)),
+    }
+}
+
+/// Applies an arithmetic or bitwise operator to two bignums, with the same rounding as `int_op`.
+fn bigint_op(op: &BinOp, lhs: BigInt, rhs: BigInt) -> Result<Value, ParserError> {
+    let div_rem = || {
+        lhs.div_rem(&rhs).ok_or_else(|| {
+            ParserError::ExprError(ExprError::DivisionByZero(op.clone(), lhs.clone()))
+        })
+    };
+
+    // The remainder has the sign of `lhs`, so the result of a floored operation is off by one when it's not zero and
+    // the signs are different
+    let rounds_down =
+        |remainder: &BigInt| !remainder.is_zero() && remainder.is_negative() != rhs.is_negative();
+
+    let result = match op {
+        BinOp::Add => &lhs + &rhs,
+        BinOp::Sub => &lhs - &rhs,
+        BinOp::Mul => &lhs * &rhs,
+        BinOp::Div => div_rem()?.0,
+        BinOp::Mod => match div_rem()? {
+            (_, remainder) if rounds_down(&remainder) => &remainder + &rhs,
+            (_, remainder) => remainder,
+        },
+        BinOp::FloorDiv => match div_rem()? {
+            (quotient, remainder) if rounds_down(&remainder) => &quotient - &BigInt::from(1),
+            (quotient, _) => quotient,
+        },
+        BinOp::Pow => {
+            let exp = rhs
+                .to_i64()
+                .and_then(|exp| u64::try_from(exp).ok())
+                .filter(|exp| lhs.bits().saturating_sub(1).saturating_mul(*exp) <= MAX_BIGNUM_BITS)
+                .ok_or_else(|| {
+                    ParserError::ExprError(ExprError::TooBig(op.clone(), lhs.clone(), rhs.clone()))
+                })?;
+
+            lhs.pow(exp)
+        }
+        BinOp::BitAnd => &lhs & &rhs,
+        BinOp::BitOr => &lhs | &rhs,
+        BinOp::BitXor => &lhs ^ &rhs,
+        BinOp::Shl | BinOp::Shr => {
+            if rhs.is_negative() {
+                return Err(ParserError::ExprError(ExprError::ShiftOutOfRange(rhs)));
+            }
+
+            // Amounts that don't fit in a `u64` can only shift everything out to the right
+            let shift = rhs.to_i64().map_or(u64::MAX, |shift| shift as u64);
+
+            if *op == BinOp::Shr {
+                &lhs >> shift
+            } else if lhs.is_zero() || lhs.bits().saturating_add(shift) <= MAX_BIGNUM_BITS {
+                &lhs << shift
+            } else {
+                return Err(ParserError::ExprError(ExprError::TooBig(
+                    op.clone(),
+                    lhs,
+                    rhs,
+                )));
+            }
+        }
+        _ => unreachable!("{op} is not an arithmetic or bitwise operator"),
+    };
+
+    // `**` and `<<` are checked before they're worked out, but the other operators can also grow a number without
+    // bound when they're repeated, such as by squaring it in a loop
+    if result.bits() > MAX_BIGNUM_BITS {
+        return Err(ParserError::ExprError(ExprError::TooBig(
+            op.clone(),
+            lhs,
+            rhs,
+        )));
+    }
+
+    Ok(Value::from(result))
+}
+
+/// Returns the remainder of `lhs ~/ rhs`, which has the same sign as `rhs`. That way `(a ~/ b) * b + a % b == a`.
+fn floor_mod<T>(lhs: T, rhs: T) -> T
+where
+    T: Copy + PartialOrd + Default + Rem<Output = T> + Add<Output = T>,
+{
+    let zero = T::default();
+    let rem = lhs % rhs;
+
+    if rem != zero && (rem < zero) != (rhs < zero) {
+        rem + rhs
+    } else {
+        rem
+    }
+}
+
+/// The numeric operands of a binary expression.
+enum Operands {
+    Ints(i64, i64),
+    BigInts(BigInt, BigInt),
+    Floats(f64, f64),
+}
+
+impl Operands {
+    /// Attempts to convert the operands into numbers. If either of them is a float then both of them become floats,
+    /// and otherwise if either of them is a bignum then both of them become bignums.
+    fn new(lhs: &Value, rhs: &Value) -> Result<Self, ParserError> {
+        let bigint = |val: &Value| match val {
+            Value::Num(num) => Some(BigInt::from(*num)),
+            Value::BigNum(num) => Some(num.clone()),
+            _ => None,
+        };
+
+        match (lhs, rhs) {
+            (Value::Num(lhs), Value::Num(rhs)) => Ok(Operands::Ints(*lhs, *rhs)),
+            (Value::Num(_) | Value::BigNum(_), Value::Num(_) | Value::BigNum(_)) => {
+                Ok(Operands::BigInts(
+                    bigint(lhs).unwrap_or_default(),
+                    bigint(rhs).unwrap_or_default(),
+                ))
+            }
+            (lhs, rhs) => Ok(Operands::Floats(
+                lhs.clone().try_into()?,
+                rhs.clone().try_into()?,
+            )),
+        }
+    }
+}